@@ -1,6 +1,5 @@
 use kentik_api::tag::*;
 use criterion::*;
-use serde_json;
 
 fn serialize_large(clients: &[(&str, &str)]) -> String {
     let upserts = clients.iter().map(|(name, ip)| {
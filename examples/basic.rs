@@ -1,6 +1,5 @@
 use std::env;
 use std::error::Error;
-use env_logger;
 use kentik_api::client::*;
 
 fn main() -> Result<(), Box<dyn Error>> {
@@ -1,6 +1,5 @@
 use std::env;
 use std::error::Error;
-use env_logger;
 use kentik_api::client::*;
 use kentik_api::core::Dimension;
 
@@ -11,7 +10,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     let token    = env::var("TOKEN").expect("env var TOKEN");
     let endpoint = env::var("ENDPOINT").unwrap_or("https://api.our1.kentik.com".to_string());
     let proxy    = env::var("PROXY").ok();
-    let proxy    = proxy.as_ref().map(String::as_str);
+    let proxy    = proxy.as_deref();
 
     let client = Client::new(&email, &token, &endpoint, proxy)?;
 
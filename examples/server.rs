@@ -1,6 +1,5 @@
 use std::time::Duration;
 use crossbeam_channel::RecvTimeoutError::*;
-use env_logger;
 use log::info;
 
 #[path="../tests/server/mod.rs"]
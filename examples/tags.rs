@@ -1,7 +1,6 @@
 use std::env;
 use std::error::Error;
 use std::time::Duration;
-use env_logger;
 use kentik_api::tag::*;
 
 fn main() -> Result<(), Box<dyn Error>> {
@@ -16,7 +15,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     let client = kentik_api::Client::new(&email, &token, &endpoint, None)?;
     let client = Client::new(client);
 
-    let users = vec![
+    let users = [
         ("alice", "10.0.0.16"),
         ("bob",   "10.0.0.32"),
         ("eve",   "10.0.0.48"),
@@ -31,14 +30,17 @@ fn main() -> Result<(), Box<dyn Error>> {
         })
     }).collect::<Vec<_>>();
 
-    client.send("c_will_test_00", Request {
+    let receipt = client.send("c_will_test_00", Request {
         replace_all: false,
         complete:    true,
         ttl_minutes: 0,
-        upserts:     upserts,
+        upserts,
         deletes:     vec![],
+        guid:        None,
     }, Duration::from_secs(1))?;
 
+    println!("submitted batch {}", receipt.wait()?);
+
     client.stop()?;
 
     Ok(())
@@ -0,0 +1,80 @@
+use serde::{Serialize, Deserialize};
+use crate::client::Client;
+use crate::Error;
+use super::{Channel, Policy};
+
+impl Client {
+    pub fn get_alert_policies(&self) -> Result<Vec<Policy>, Error> {
+        let url = format!("{}/alert_policy/v202303/policies", self.endpoint());
+
+        #[derive(Serialize, Deserialize, Debug)]
+        struct Wrapper {
+            #[serde(default)]
+            policies: Vec<Policy>,
+        }
+
+        Ok(self.get::<Wrapper>(&url)?.policies)
+    }
+
+    pub fn get_alert_policy(&self, id: &str) -> Result<Policy, Error> {
+        let url = format!("{}/alert_policy/v202303/policies/{}", self.endpoint(), id);
+        Ok(self.get::<PolicyWrapper<_>>(&url)?.policy)
+    }
+
+    pub fn create_alert_policy(&self, policy: &Policy) -> Result<Policy, Error> {
+        let url = format!("{}/alert_policy/v202303/policies", self.endpoint());
+        Ok(self.post::<_, PolicyWrapper<_>>(&url, &PolicyWrapper { policy })?.policy)
+    }
+
+    pub fn update_alert_policy(&self, policy: &Policy) -> Result<Policy, Error> {
+        let url = format!("{}/alert_policy/v202303/policies/{}", self.endpoint(), policy.id);
+        Ok(self.put::<_, PolicyWrapper<_>>(&url, &PolicyWrapper { policy })?.policy)
+    }
+
+    pub fn delete_alert_policy(&self, id: &str) -> Result<(), Error> {
+        let url = format!("{}/alert_policy/v202303/policies/{}", self.endpoint(), id);
+        self.delete(&url)
+    }
+
+    pub fn get_notification_channels(&self) -> Result<Vec<Channel>, Error> {
+        let url = format!("{}/notification/v202210/channels", self.endpoint());
+
+        #[derive(Serialize, Deserialize, Debug)]
+        struct Wrapper {
+            #[serde(default)]
+            channels: Vec<Channel>,
+        }
+
+        Ok(self.get::<Wrapper>(&url)?.channels)
+    }
+
+    pub fn get_notification_channel(&self, id: &str) -> Result<Channel, Error> {
+        let url = format!("{}/notification/v202210/channels/{}", self.endpoint(), id);
+        Ok(self.get::<ChannelWrapper<_>>(&url)?.channel)
+    }
+
+    pub fn create_notification_channel(&self, channel: &Channel) -> Result<Channel, Error> {
+        let url = format!("{}/notification/v202210/channels", self.endpoint());
+        Ok(self.post::<_, ChannelWrapper<_>>(&url, &ChannelWrapper { channel })?.channel)
+    }
+
+    pub fn update_notification_channel(&self, channel: &Channel) -> Result<Channel, Error> {
+        let url = format!("{}/notification/v202210/channels/{}", self.endpoint(), channel.id);
+        Ok(self.put::<_, ChannelWrapper<_>>(&url, &ChannelWrapper { channel })?.channel)
+    }
+
+    pub fn delete_notification_channel(&self, id: &str) -> Result<(), Error> {
+        let url = format!("{}/notification/v202210/channels/{}", self.endpoint(), id);
+        self.delete(&url)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct PolicyWrapper<T> {
+    policy: T,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct ChannelWrapper<T> {
+    channel: T,
+}
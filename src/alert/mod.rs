@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
+
+pub mod client;
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Policy {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id:                    String,
+    pub name:                  String,
+    #[serde(default)]
+    pub description:           String,
+    #[serde(default)]
+    pub enabled:               bool,
+    #[serde(default)]
+    pub dimensions:            Vec<String>,
+    #[serde(default)]
+    pub metrics:               Vec<String>,
+    #[serde(default)]
+    pub thresholds:            Vec<Threshold>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline:              Option<Baseline>,
+    #[serde(default)]
+    pub activation:            Activation,
+    #[serde(default)]
+    pub notification_channels: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Threshold {
+    pub severity:              Severity,
+    pub conditions:            Vec<Condition>,
+    #[serde(default)]
+    pub notification_channels: Vec<String>,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, Debug)]
+pub enum Severity {
+    #[serde(rename = "SEVERITY_MINOR")]
+    Minor,
+    #[serde(rename = "SEVERITY_MAJOR")]
+    Major,
+    #[serde(rename = "SEVERITY_CRITICAL")]
+    Critical,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct Condition {
+    pub metric:     String,
+    pub comparison: Comparison,
+    pub value:      f64,
+    #[serde(default)]
+    pub baseline:   bool,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, Debug)]
+pub enum Comparison {
+    #[serde(rename = "COMPARISON_GREATER_THAN")]
+    GreaterThan,
+    #[serde(rename = "COMPARISON_GREATER_THAN_OR_EQUAL")]
+    GreaterThanOrEqual,
+    #[serde(rename = "COMPARISON_LESS_THAN")]
+    LessThan,
+    #[serde(rename = "COMPARISON_LESS_THAN_OR_EQUAL")]
+    LessThanOrEqual,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Baseline {
+    pub lookback_days: u32,
+    pub aggregation:   String,
+    pub fallback:      f64,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Activation {
+    pub time_window:  u32,
+    pub times:        u32,
+    pub grace_period: u32,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub struct Channel {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id:      String,
+    pub name:    String,
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub target:  Target,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(tag = "type", content = "config", rename_all = "lowercase")]
+pub enum Target {
+    Email(Email),
+    Slack(Slack),
+    PagerDuty(PagerDuty),
+    Webhook(Webhook),
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub struct Email {
+    pub recipients: Vec<String>,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Slack {
+    pub webhook_url: String,
+    #[serde(default)]
+    pub channel:     String,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PagerDuty {
+    pub routing_key: String,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub struct Webhook {
+    pub url:     String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
@@ -16,7 +16,7 @@ pub struct Retry {
 pub fn retry<F: FutureFactory>(factory: F, retries: usize) -> FutureRetry<F, Retry> {
     FutureRetry::new(factory, Retry {
         backoff: ExponentialBackoff::default(),
-        retries: retries,
+        retries,
     })
 }
 
@@ -81,6 +81,7 @@ mod test {
         assert_eq!(Stop, retry(1).handle(0, App(String::new(), 400)).into());
         assert_eq!(Stop, retry(1).handle(0, Status(400)).into());
         assert_eq!(Stop, retry(1).handle(0, Empty).into());
+        assert_eq!(Stop, retry(1).handle(0, Invalid(String::new())).into());
     }
 
     fn retry(retries: usize) -> Retry {
@@ -0,0 +1,67 @@
+use serde::{Serialize, Deserialize};
+use crate::client::Client;
+use crate::Error;
+use super::{Monitor, MetricsQuery, PrefixMetrics, Route, RoutesQuery};
+
+impl Client {
+    pub fn get_bgp_monitors(&self) -> Result<Vec<Monitor>, Error> {
+        let url = format!("{}/bgp_monitoring/v202205beta1/monitors", self.endpoint());
+
+        #[derive(Serialize, Deserialize, Debug)]
+        struct Wrapper {
+            #[serde(default)]
+            monitors: Vec<Monitor>,
+        }
+
+        Ok(self.get::<Wrapper>(&url)?.monitors)
+    }
+
+    pub fn get_bgp_monitor(&self, id: &str) -> Result<Monitor, Error> {
+        let url = format!("{}/bgp_monitoring/v202205beta1/monitors/{}", self.endpoint(), id);
+        Ok(self.get::<MonitorWrapper<_>>(&url)?.monitor)
+    }
+
+    pub fn create_bgp_monitor(&self, monitor: &Monitor) -> Result<Monitor, Error> {
+        let url = format!("{}/bgp_monitoring/v202205beta1/monitors", self.endpoint());
+        Ok(self.post::<_, MonitorWrapper<_>>(&url, &MonitorWrapper { monitor })?.monitor)
+    }
+
+    pub fn update_bgp_monitor(&self, monitor: &Monitor) -> Result<Monitor, Error> {
+        let url = format!("{}/bgp_monitoring/v202205beta1/monitors/{}", self.endpoint(), monitor.id);
+        Ok(self.put::<_, MonitorWrapper<_>>(&url, &MonitorWrapper { monitor })?.monitor)
+    }
+
+    pub fn delete_bgp_monitor(&self, id: &str) -> Result<(), Error> {
+        let url = format!("{}/bgp_monitoring/v202205beta1/monitors/{}", self.endpoint(), id);
+        self.delete(&url)
+    }
+
+    pub fn get_bgp_metrics(&self, q: &MetricsQuery) -> Result<Vec<PrefixMetrics>, Error> {
+        let url = format!("{}/bgp_monitoring/v202205beta1/metrics", self.endpoint());
+
+        #[derive(Serialize, Deserialize, Debug)]
+        struct Wrapper {
+            #[serde(default)]
+            data: Vec<PrefixMetrics>,
+        }
+
+        Ok(self.post::<_, Wrapper>(&url, q)?.data)
+    }
+
+    pub fn get_bgp_routes(&self, q: &RoutesQuery) -> Result<Vec<Route>, Error> {
+        let url = format!("{}/bgp_monitoring/v202205beta1/routes", self.endpoint());
+
+        #[derive(Serialize, Deserialize, Debug)]
+        struct Wrapper {
+            #[serde(default)]
+            routes: Vec<Route>,
+        }
+
+        Ok(self.post::<_, Wrapper>(&url, q)?.routes)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct MonitorWrapper<T> {
+    monitor: T,
+}
@@ -0,0 +1,124 @@
+use std::net::IpAddr;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use crate::net::Cidr;
+
+pub mod client;
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct Monitor {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id:       String,
+    pub name:     String,
+    #[serde(default)]
+    pub status:   MonitorStatus,
+    pub settings: Settings,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, Debug, Default)]
+pub enum MonitorStatus {
+    #[default]
+    #[serde(rename = "BGP_MONITOR_STATUS_ACTIVE")]
+    Active,
+    #[serde(rename = "BGP_MONITOR_STATUS_PAUSED")]
+    Paused,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub targets:                  Vec<Cidr>,
+    pub allowed_asns:             Vec<u32>,
+    pub check_rpki:               bool,
+    pub include_covered_prefixes: bool,
+    pub health_settings:          HealthSettings,
+    pub notification_channels:    Vec<String>,
+    pub tags:                     Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HealthSettings {
+    pub reachability_warning:  f64,
+    pub reachability_critical: f64,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricsQuery {
+    pub start_time:      DateTime<Utc>,
+    pub end_time:        DateTime<Utc>,
+    pub prefix:          Cidr,
+    pub include_covered: bool,
+    pub metrics:         Vec<MetricKind>,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, Debug)]
+pub enum MetricKind {
+    #[serde(rename = "METRIC_TYPE_REACHABILITY")]
+    Reachability,
+    #[serde(rename = "METRIC_TYPE_PATH_CHANGES")]
+    PathChanges,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct PrefixMetrics {
+    pub prefix:  Cidr,
+    #[serde(default)]
+    pub metrics: Vec<Metric>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct Metric {
+    pub time:  DateTime<Utc>,
+    #[serde(rename = "type")]
+    pub kind:  MetricKind,
+    pub value: f64,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RoutesQuery {
+    pub prefix:          Cidr,
+    pub include_covered: bool,
+    pub check_rpki:      bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time:            Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Route {
+    pub prefix:        Cidr,
+    #[serde(default)]
+    pub as_path:       Vec<u32>,
+    #[serde(default)]
+    pub origin_asn:    u32,
+    #[serde(default)]
+    pub next_hop:      Option<IpAddr>,
+    #[serde(default)]
+    pub rpki_status:   RpkiStatus,
+    #[serde(default)]
+    pub vantage_point: VantagePoint,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, Debug, Default)]
+pub enum RpkiStatus {
+    #[serde(rename = "RPKI_STATUS_VALID")]
+    Valid,
+    #[serde(rename = "RPKI_STATUS_INVALID")]
+    Invalid,
+    #[serde(rename = "RPKI_STATUS_NOT_FOUND")]
+    NotFound,
+    #[default]
+    #[serde(other, rename = "RPKI_STATUS_UNSPECIFIED")]
+    Unknown,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct VantagePoint {
+    pub collector: String,
+    pub peer_asn:  u32,
+    pub peer_ip:   String,
+}
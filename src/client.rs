@@ -47,6 +47,20 @@ impl Client {
         }), self.retries)
     }
 
+    pub fn put<T: Serialize, U: DeserializeOwned>(&self, url: &str, body: &T) -> Result<U, Error> {
+        retry(|n| send(self.client.put(url).json(body)).map_err(|err| {
+            debug!("PUT {} #{} failed: {}", url, n, err);
+            err
+        }), self.retries)
+    }
+
+    pub fn delete(&self, url: &str) -> Result<(), Error> {
+        retry(|n| execute(self.client.delete(url)).map(drop).map_err(|err| {
+            debug!("DELETE {} #{} failed: {}", url, n, err);
+            err
+        }), self.retries)
+    }
+
     pub fn endpoint(&self) -> &str {
         &self.endpoint
     }
@@ -69,6 +83,10 @@ fn retry<T>(mut op: impl FnMut(u64) -> Result<T, Error>, retries: u64) -> Result
 }
 
 fn send<T: DeserializeOwned>(r: RequestBuilder) -> Result<T, Error> {
+    Ok(execute(r)?.json()?)
+}
+
+fn execute(r: RequestBuilder) -> Result<Response, Error> {
     let response = r.send()?;
     let status   = response.status();
 
@@ -85,7 +103,7 @@ fn send<T: DeserializeOwned>(r: RequestBuilder) -> Result<T, Error> {
     };
 
     match status {
-        _ if status.is_success() => Ok(response.json()?),
+        _ if status.is_success() => Ok(response),
         StatusCode::UNAUTHORIZED => Err(Error::Auth),
         _                        => Err(error(response)),
     }
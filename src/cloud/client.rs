@@ -0,0 +1,43 @@
+use serde::{Serialize, Deserialize};
+use crate::client::Client;
+use crate::Error;
+use super::Export;
+
+impl Client {
+    pub fn get_cloud_exports(&self) -> Result<Vec<Export>, Error> {
+        let url = format!("{}/cloud_export/v202210/exports", self.endpoint());
+
+        #[derive(Serialize, Deserialize, Debug)]
+        struct Wrapper {
+            #[serde(default)]
+            exports: Vec<Export>,
+        }
+
+        Ok(self.get::<Wrapper>(&url)?.exports)
+    }
+
+    pub fn get_cloud_export(&self, id: &str) -> Result<Export, Error> {
+        let url = format!("{}/cloud_export/v202210/exports/{}", self.endpoint(), id);
+        Ok(self.get::<ExportWrapper<_>>(&url)?.export)
+    }
+
+    pub fn create_cloud_export(&self, export: &Export) -> Result<Export, Error> {
+        let url = format!("{}/cloud_export/v202210/exports", self.endpoint());
+        Ok(self.post::<_, ExportWrapper<_>>(&url, &ExportWrapper { export })?.export)
+    }
+
+    pub fn update_cloud_export(&self, export: &Export) -> Result<Export, Error> {
+        let url = format!("{}/cloud_export/v202210/exports/{}", self.endpoint(), export.id);
+        Ok(self.put::<_, ExportWrapper<_>>(&url, &ExportWrapper { export })?.export)
+    }
+
+    pub fn delete_cloud_export(&self, id: &str) -> Result<(), Error> {
+        let url = format!("{}/cloud_export/v202210/exports/{}", self.endpoint(), id);
+        self.delete(&url)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct ExportWrapper<T> {
+    export: T,
+}
@@ -0,0 +1,155 @@
+use std::convert::TryFrom;
+use serde::{Serialize, Deserialize};
+
+pub mod client;
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(into = "Wire", try_from = "Wire")]
+pub struct Export {
+    pub id:          String,
+    pub name:        String,
+    pub description: String,
+    pub kind:        ExportKind,
+    pub enabled:     bool,
+    pub plan_id:     String,
+    pub provider:    Provider,
+    pub status:      Option<Status>,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, Debug, Default)]
+pub enum ExportKind {
+    #[default]
+    #[serde(rename = "CLOUD_EXPORT_TYPE_KENTIK_MANAGED")]
+    KentikManaged,
+    #[serde(rename = "CLOUD_EXPORT_TYPE_CUSTOMER_MANAGED")]
+    CustomerManaged,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Provider {
+    Aws(Aws),
+    Azure(Azure),
+    Gcp(Gcp),
+    Ibm(Ibm),
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Aws {
+    pub bucket:            String,
+    pub iam_role_arn:      String,
+    pub region:            String,
+    pub delete_after_read: bool,
+    pub multiple_buckets:  bool,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Azure {
+    pub location:                   String,
+    pub resource_group:             String,
+    pub storage_account:            String,
+    pub subscription_id:            String,
+    pub security_principal_enabled: bool,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Gcp {
+    pub project:      String,
+    pub subscription: String,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Ibm {
+    pub bucket: String,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Status {
+    pub status:                 String,
+    pub error_message:          String,
+    pub flow_found:             bool,
+    pub api_access:             bool,
+    pub storage_account_access: bool,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct Wire {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    id:             String,
+    #[serde(default, rename = "type")]
+    kind:           ExportKind,
+    #[serde(default)]
+    enabled:        bool,
+    name:           String,
+    #[serde(default)]
+    description:    String,
+    #[serde(default)]
+    plan_id:        String,
+    cloud_provider: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    aws:            Option<Aws>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    azure:          Option<Azure>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    gce:            Option<Gcp>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ibm:            Option<Ibm>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    current_status: Option<Status>,
+}
+
+impl From<Export> for Wire {
+    fn from(e: Export) -> Self {
+        let mut wire = Wire {
+            id:             e.id,
+            kind:           e.kind,
+            enabled:        e.enabled,
+            name:           e.name,
+            description:    e.description,
+            plan_id:        e.plan_id,
+            current_status: e.status,
+            ..Default::default()
+        };
+
+        match e.provider {
+            Provider::Aws(aws)     => { wire.cloud_provider = "aws".to_owned();   wire.aws   = Some(aws)   },
+            Provider::Azure(azure) => { wire.cloud_provider = "azure".to_owned(); wire.azure = Some(azure) },
+            Provider::Gcp(gcp)     => { wire.cloud_provider = "gce".to_owned();   wire.gce   = Some(gcp)   },
+            Provider::Ibm(ibm)     => { wire.cloud_provider = "ibm".to_owned();   wire.ibm   = Some(ibm)   },
+        };
+
+        wire
+    }
+}
+
+impl TryFrom<Wire> for Export {
+    type Error = String;
+
+    fn try_from(w: Wire) -> Result<Self, Self::Error> {
+        let Wire { cloud_provider, aws, azure, gce, ibm, .. } = w;
+
+        let provider = match cloud_provider.as_str() {
+            "aws"   => aws.map(Provider::Aws),
+            "azure" => azure.map(Provider::Azure),
+            "gce"   => gce.map(Provider::Gcp),
+            "ibm"   => ibm.map(Provider::Ibm),
+            other   => return Err(format!("unsupported cloud provider {}", other)),
+        }.ok_or_else(|| format!("missing {} properties", cloud_provider))?;
+
+        Ok(Export {
+            id:          w.id,
+            name:        w.name,
+            description: w.description,
+            kind:        w.kind,
+            enabled:     w.enabled,
+            plan_id:     w.plan_id,
+            provider,
+            status:      w.current_status,
+        })
+    }
+}
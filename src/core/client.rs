@@ -1,8 +1,8 @@
 use serde::{Serialize, Deserialize};
 use crate::client::Client;
 use crate::Error;
-use crate::tag::{Request, Response};
-use super::{Device, Dimension, Dimensions};
+use crate::tag::{Request, Response, BATCH_LIMIT};
+use super::{Device, Dimension, Dimensions, Populator};
 
 impl Client {
     pub fn get_device_by_name(&self, name: &str) -> Result<Device, Error> {
@@ -33,8 +33,35 @@ impl Client {
         Ok(self.post::<_, Wrapper>(&url, d)?.dimension)
     }
 
+    pub fn get_populators(&self, column: &str) -> Result<Vec<Populator>, Error> {
+        let url = format!("{}/api/internal/customdimension/{}/populators", self.endpoint(), column);
+
+        #[derive(Serialize, Deserialize, Debug)]
+        struct Wrapper {
+            #[serde(default)]
+            populators: Vec<Populator>,
+        }
+
+        Ok(self.get::<Wrapper>(&url)?.populators)
+    }
+
     pub fn update_populators(&self, column: &str, r: &Request) -> Result<Response, Error> {
         let url = format!("{}/api/internal/batch/customdimensions/{}/populators", self.endpoint(), column);
-        self.post(&url, r)
+
+        if r.len() <= BATCH_LIMIT {
+            return self.post(&url, r);
+        }
+
+        let mut guid = r.guid.clone();
+        let mut last = Err(Error::Empty);
+
+        for mut chunk in r.chunks(BATCH_LIMIT) {
+            chunk.guid = guid.take();
+            let response: Response = self.post(&url, &chunk)?;
+            guid = Some(response.guid.clone());
+            last = Ok(response);
+        }
+
+        last
     }
 }
@@ -22,7 +22,7 @@ pub struct Dimensions {
     pub dimensions: Vec<Dimension>,
 }
 
-#[derive(Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
 pub struct Dimension {
     pub id:           u64,
     pub name:         String,
@@ -35,6 +35,56 @@ pub struct Dimension {
     pub internal:     bool,
 }
 
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Populator {
+    pub id:              u64,
+    #[serde(deserialize_with = "nullable")]
+    pub value:           String,
+    #[serde(deserialize_with = "nullable")]
+    pub direction:       String,
+    #[serde(deserialize_with = "nullable")]
+    pub port:            String,
+    #[serde(deserialize_with = "nullable")]
+    pub protocol:        String,
+    #[serde(deserialize_with = "nullable")]
+    pub asn:             String,
+    #[serde(deserialize_with = "nullable")]
+    pub vlans:           String,
+    #[serde(deserialize_with = "nullable")]
+    pub lasthop_as_name: String,
+    #[serde(deserialize_with = "nullable")]
+    pub nexthop_asn:     String,
+    #[serde(deserialize_with = "nullable")]
+    pub nexthop_as_name: String,
+    #[serde(deserialize_with = "nullable")]
+    pub bgp_aspath:      String,
+    #[serde(deserialize_with = "nullable")]
+    pub bgp_community:   String,
+    #[serde(deserialize_with = "nullable")]
+    pub tcp_flags:       String,
+    #[serde(deserialize_with = "nullable")]
+    pub addr:            String,
+    #[serde(deserialize_with = "nullable")]
+    pub mac:             String,
+    #[serde(deserialize_with = "nullable")]
+    pub country:         String,
+    #[serde(deserialize_with = "nullable")]
+    pub site:            String,
+    #[serde(deserialize_with = "nullable")]
+    pub device_type:     String,
+    #[serde(deserialize_with = "nullable")]
+    pub interface_name:  String,
+    #[serde(deserialize_with = "nullable")]
+    pub device_name:     String,
+    #[serde(deserialize_with = "nullable")]
+    pub next_hop:        String,
+}
+
+fn nullable<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(Option::<String>::deserialize(deserializer)?.unwrap_or_default())
+}
+
 fn from_str<'de, T, D>(deserializer: D) -> Result<T, D::Error>
     where T: FromStr,
           T::Err: Display,
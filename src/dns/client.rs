@@ -67,9 +67,9 @@ fn poll(rx: Receiver<Vec<Response>>, c: AsyncClient) -> Result<(), Error> {
 
 fn encode(buf: &mut Vec<u8>, rs: &[Response]) -> Result<(), Error> {
     let mut s = Serializer::new(buf).with_struct_map();
-    rs.iter().map(|r| {
+    rs.iter().try_for_each(|r| {
         Ok(r.serialize(&mut s)?)
-    }).collect::<Result<_, _>>()
+    })
 }
 
 fn send(rt: &Runtime, client: &AsyncClient, buf: &mut Vec<u8>) {
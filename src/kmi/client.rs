@@ -0,0 +1,51 @@
+use serde::{Serialize, Deserialize};
+use crate::client::Client;
+use crate::Error;
+use super::{AsnDetails, IpVersion, Market, Ranking, RankingsQuery};
+
+impl Client {
+    pub fn get_kmi_markets(&self) -> Result<Vec<Market>, Error> {
+        let url = format!("{}/kmi/v202212/markets", self.endpoint());
+
+        #[derive(Serialize, Deserialize, Debug)]
+        struct Wrapper {
+            #[serde(default)]
+            markets: Vec<Market>,
+        }
+
+        Ok(self.get::<Wrapper>(&url)?.markets)
+    }
+
+    pub fn get_kmi_rankings(&self, market: &str, q: &RankingsQuery) -> Result<Vec<Ranking>, Error> {
+        let url = format!("{}/kmi/v202212/market/{}/rankings", self.endpoint(), market);
+
+        #[derive(Serialize, Deserialize, Debug)]
+        struct Wrapper {
+            #[serde(default)]
+            rankings: Vec<Ranking>,
+        }
+
+        Ok(self.post::<_, Wrapper>(&url, q)?.rankings)
+    }
+
+    pub fn get_kmi_asn_details(&self, asn: u32, market: &str, ip: IpVersion) -> Result<AsnDetails, Error> {
+        let url = format!("{}/kmi/v202212/asn/{}", self.endpoint(), asn);
+
+        #[derive(Serialize, Deserialize, Debug)]
+        #[serde(rename_all = "camelCase")]
+        struct Query<'a> {
+            market_id:  &'a str,
+            ip_version: IpVersion,
+        }
+
+        #[derive(Serialize, Deserialize, Debug)]
+        #[serde(rename_all = "camelCase")]
+        struct Wrapper {
+            asn_details: AsnDetails,
+        }
+
+        let query = Query { market_id: market, ip_version: ip };
+
+        Ok(self.post::<_, Wrapper>(&url, &query)?.asn_details)
+    }
+}
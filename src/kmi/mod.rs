@@ -0,0 +1,66 @@
+use serde::{Serialize, Deserialize};
+
+pub mod client;
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Market {
+    pub id:          String,
+    pub name:        String,
+    pub market_type: String,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum RankKind {
+    CustomerBase,
+    Provider,
+    Peering,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum IpVersion {
+    #[default]
+    Ipv4,
+    Ipv6,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RankingsQuery {
+    pub rank_type:  RankKind,
+    pub ip_version: IpVersion,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit:      Option<u32>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Ranking {
+    pub asn:          u32,
+    pub name:         String,
+    pub rank:         u32,
+    pub rank_change:  i32,
+    pub score:        f64,
+    pub score_change: f64,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct AsnDetails {
+    pub asn:       u32,
+    pub name:      String,
+    pub country:   String,
+    pub customers: Vec<Relation>,
+    pub providers: Vec<Relation>,
+    pub peers:     Vec<Relation>,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Relation {
+    pub asn:     u32,
+    pub name:    String,
+    pub country: String,
+}
@@ -1,10 +1,17 @@
 use std::fmt::{self, Display};
-use reqwest;
 
 pub mod client;
 pub mod r#async;
+pub mod alert;
+pub mod bgp;
+pub mod cloud;
 pub mod core;
 pub mod dns;
+pub mod kmi;
+pub mod net;
+pub mod netclass;
+pub mod page;
+pub mod synth;
 pub mod tag;
 
 pub use client::Client;
@@ -17,6 +24,7 @@ pub enum Error {
     Status(u16),
     Empty,
     Timeout,
+    Invalid(String),
     Other(String),
 }
 
@@ -24,6 +32,7 @@ impl Error {
     fn into_backoff(self) -> backoff::Error<Self> {
         match self {
             Error::Auth | Error::Empty => backoff::Error::Permanent(self),
+            Error::Invalid(_)          => backoff::Error::Permanent(self),
             Error::App(_, 300..=499)   => backoff::Error::Permanent(self),
             Error::Status(300..=499)   => backoff::Error::Permanent(self),
             _                          => backoff::Error::transient(self),
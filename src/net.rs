@@ -0,0 +1,487 @@
+use std::convert::TryFrom;
+use std::fmt::{self, Display};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+use serde::{Serialize, Deserialize};
+use serde::de::{self, Deserializer};
+use serde::ser::Serializer;
+use crate::Error;
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Cidr {
+    addr: IpAddr,
+    len:  u8,
+}
+
+impl Cidr {
+    pub fn new(addr: IpAddr, len: u8) -> Result<Self, Error> {
+        if len > width(&addr) {
+            return Err(Error::Invalid(format!("invalid prefix length {}/{}", addr, len)));
+        }
+
+        if mask(addr, len) != addr {
+            return Err(Error::Invalid(format!("host bits set in {}/{}", addr, len)));
+        }
+
+        Ok(Self { addr, len })
+    }
+
+    pub fn host(addr: IpAddr) -> Self {
+        Self { addr, len: width(&addr) }
+    }
+
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    pub fn prefix_len(&self) -> u8 {
+        self.len
+    }
+
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        self.addr.is_ipv4() == addr.is_ipv4() && mask(*addr, self.len) == self.addr
+    }
+
+    pub fn is_host(&self) -> bool {
+        self.len == width(&self.addr)
+    }
+
+    fn bounds(&self) -> (u128, u128) {
+        let lo = bits(self.addr);
+        (lo, lo + ones(width(&self.addr) - self.len))
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Range {
+    start: IpAddr,
+    end:   IpAddr,
+}
+
+impl Range {
+    pub fn new(start: IpAddr, end: IpAddr) -> Result<Self, Error> {
+        if start.is_ipv4() != end.is_ipv4() || start > end {
+            return Err(Error::Invalid(format!("invalid IP range {}-{}", start, end)));
+        }
+        Ok(Range { start, end })
+    }
+
+    pub fn start(&self) -> IpAddr {
+        self.start
+    }
+
+    pub fn end(&self) -> IpAddr {
+        self.end
+    }
+
+    pub fn cidrs(&self) -> Vec<Cidr> {
+        blocks(self.start.is_ipv6(), bits(self.start), bits(self.end))
+    }
+}
+
+impl Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+pub fn range(start: IpAddr, end: IpAddr) -> Result<Vec<Cidr>, Error> {
+    Ok(Range::new(start, end)?.cidrs())
+}
+
+pub fn aggregate<I: IntoIterator<Item = Cidr>>(cidrs: I) -> Vec<Cidr> {
+    let mut ranges = cidrs.into_iter().map(|cidr| {
+        let (lo, hi) = cidr.bounds();
+        (cidr.addr.is_ipv6(), lo, hi)
+    }).collect::<Vec<_>>();
+
+    ranges.sort_unstable();
+
+    let mut merged: Vec<(bool, u128, u128)> = Vec::with_capacity(ranges.len());
+    for (v6, lo, hi) in ranges {
+        match merged.last_mut() {
+            Some(last) if last.0 == v6 && lo <= last.2.saturating_add(1) => last.2 = last.2.max(hi),
+            _                                                           => merged.push((v6, lo, hi)),
+        }
+    }
+
+    merged.into_iter().flat_map(|(v6, lo, hi)| blocks(v6, lo, hi)).collect()
+}
+
+impl Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.len)
+    }
+}
+
+impl FromStr for Cidr {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::Invalid(format!("invalid CIDR {}", s));
+
+        let mut split = s.splitn(2, '/');
+        let addr = split.next().unwrap_or_default();
+        let addr = addr.parse::<IpAddr>().map_err(|_| invalid())?;
+
+        match split.next() {
+            Some(len) => Cidr::new(addr, len.parse().map_err(|_| invalid())?),
+            None      => Ok(Cidr::host(addr)),
+        }
+    }
+}
+
+impl From<IpAddr> for Cidr {
+    fn from(addr: IpAddr) -> Self {
+        Cidr::host(addr)
+    }
+}
+
+impl Serialize for Cidr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Cidr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct MacAddr([u8; 6]);
+
+impl MacAddr {
+    pub fn new(octets: [u8; 6]) -> Self {
+        MacAddr(octets)
+    }
+
+    pub fn octets(&self) -> [u8; 6] {
+        self.0
+    }
+}
+
+impl Display for MacAddr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let [a, b, c, d, e, g] = self.0;
+        write!(f, "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}", a, b, c, d, e, g)
+    }
+}
+
+impl FromStr for MacAddr {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::Invalid(format!("invalid MAC address {}", s));
+
+        let mut octets = [0u8; 6];
+        let mut split  = s.split([':', '-']);
+
+        for octet in octets.iter_mut() {
+            let part = split.next().filter(|p| p.len() == 2).ok_or_else(invalid)?;
+            *octet = u8::from_str_radix(part, 16).map_err(|_| invalid())?;
+        }
+
+        match split.next() {
+            Some(_) => Err(invalid()),
+            None    => Ok(MacAddr(octets)),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Country([u8; 2]);
+
+impl Country {
+    pub fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.0).unwrap_or_default()
+    }
+}
+
+impl Display for Country {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Country {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let code = s.to_ascii_uppercase();
+        match COUNTRIES.binary_search(&code.as_str()) {
+            Ok(_)  => Ok(Country([code.as_bytes()[0], code.as_bytes()[1]])),
+            Err(_) => Err(Error::Invalid(format!("invalid country code {}", s))),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Community {
+    Standard(u16, u16),
+    Asn4(u32, u32),
+    Large(u32, u32, u32),
+}
+
+impl Display for Community {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Community::Standard(asn, value) => write!(f, "{}:{}", asn, value),
+            Community::Asn4(asn, value)     => write!(f, "{}:{}", asn, value),
+            Community::Large(asn, a, b)     => write!(f, "{}:{}:{}", asn, a, b),
+        }
+    }
+}
+
+impl FromStr for Community {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::Invalid(format!("invalid BGP community {}", s));
+
+        let parts = s.split(':').map(str::parse::<u32>).collect::<Result<Vec<_>, _>>();
+        let parts = parts.map_err(|_| invalid())?;
+
+        match parts[..] {
+            [asn, value] => match (u16::try_from(asn), u16::try_from(value)) {
+                (Ok(asn), Ok(value)) => Ok(Community::Standard(asn, value)),
+                _                    => Ok(Community::Asn4(asn, value)),
+            },
+            [asn, a, b] => Ok(Community::Large(asn, a, b)),
+            _           => Err(invalid()),
+        }
+    }
+}
+
+// a literal community, or a regex matched against communities as text
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum CommunityMatch {
+    Literal(Community),
+    Regex(String),
+}
+
+impl Display for CommunityMatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CommunityMatch::Literal(c) => write!(f, "{}", c),
+            CommunityMatch::Regex(re)  => write!(f, "{}", re),
+        }
+    }
+}
+
+impl FromStr for CommunityMatch {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.contains(|c| "^$.*+?[]()|\\".contains(c)) {
+            true  => Ok(CommunityMatch::Regex(s.to_owned())),
+            false => Ok(CommunityMatch::Literal(s.parse()?)),
+        }
+    }
+}
+
+// split an inclusive address range into the fewest aligned prefixes
+fn blocks(v6: bool, mut lo: u128, hi: u128) -> Vec<Cidr> {
+    let width  = if v6 { 128 } else { 32 };
+    let mut vec = Vec::new();
+
+    loop {
+        let mut size = match lo {
+            0 => width,
+            _ => lo.trailing_zeros().min(width as u32) as u8,
+        };
+
+        while size > 0 && ones(size) > hi - lo {
+            size -= 1;
+        }
+
+        let addr = match v6 {
+            true  => IpAddr::V6(Ipv6Addr::from(lo)),
+            false => IpAddr::V4(Ipv4Addr::from(lo as u32)),
+        };
+        vec.push(Cidr { addr, len: width - size });
+
+        let last = lo + ones(size);
+        if last >= hi {
+            break;
+        }
+        lo = last + 1;
+    }
+
+    vec
+}
+
+fn bits(addr: IpAddr) -> u128 {
+    match addr {
+        IpAddr::V4(ip) => u32::from(ip).into(),
+        IpAddr::V6(ip) => ip.into(),
+    }
+}
+
+fn ones(n: u8) -> u128 {
+    u128::MAX.checked_shr(128 - n as u32).unwrap_or(0)
+}
+
+fn width(addr: &IpAddr) -> u8 {
+    match addr {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    }
+}
+
+fn mask(addr: IpAddr, len: u8) -> IpAddr {
+    match addr {
+        IpAddr::V4(ip) => {
+            let bits = u32::from(ip) & u32::MAX.checked_shl(32 - len as u32).unwrap_or(0);
+            IpAddr::V4(Ipv4Addr::from(bits))
+        },
+        IpAddr::V6(ip) => {
+            let bits = u128::from(ip) & u128::MAX.checked_shl(128 - len as u32).unwrap_or(0);
+            IpAddr::V6(Ipv6Addr::from(bits))
+        },
+    }
+}
+
+// ISO 3166-1 alpha-2
+const COUNTRIES: &[&str] = &[
+    "AD", "AE", "AF", "AG", "AI", "AL", "AM", "AO", "AQ", "AR", "AS", "AT", "AU", "AW", "AX", "AZ",
+    "BA", "BB", "BD", "BE", "BF", "BG", "BH", "BI", "BJ", "BL", "BM", "BN", "BO", "BQ", "BR", "BS",
+    "BT", "BV", "BW", "BY", "BZ", "CA", "CC", "CD", "CF", "CG", "CH", "CI", "CK", "CL", "CM", "CN",
+    "CO", "CR", "CU", "CV", "CW", "CX", "CY", "CZ", "DE", "DJ", "DK", "DM", "DO", "DZ", "EC", "EE",
+    "EG", "EH", "ER", "ES", "ET", "FI", "FJ", "FK", "FM", "FO", "FR", "GA", "GB", "GD", "GE", "GF",
+    "GG", "GH", "GI", "GL", "GM", "GN", "GP", "GQ", "GR", "GS", "GT", "GU", "GW", "GY", "HK", "HM",
+    "HN", "HR", "HT", "HU", "ID", "IE", "IL", "IM", "IN", "IO", "IQ", "IR", "IS", "IT", "JE", "JM",
+    "JO", "JP", "KE", "KG", "KH", "KI", "KM", "KN", "KP", "KR", "KW", "KY", "KZ", "LA", "LB", "LC",
+    "LI", "LK", "LR", "LS", "LT", "LU", "LV", "LY", "MA", "MC", "MD", "ME", "MF", "MG", "MH", "MK",
+    "ML", "MM", "MN", "MO", "MP", "MQ", "MR", "MS", "MT", "MU", "MV", "MW", "MX", "MY", "MZ", "NA",
+    "NC", "NE", "NF", "NG", "NI", "NL", "NO", "NP", "NR", "NU", "NZ", "OM", "PA", "PE", "PF", "PG",
+    "PH", "PK", "PL", "PM", "PN", "PR", "PS", "PT", "PW", "PY", "QA", "RE", "RO", "RS", "RU", "RW",
+    "SA", "SB", "SC", "SD", "SE", "SG", "SH", "SI", "SJ", "SK", "SL", "SM", "SN", "SO", "SR", "SS",
+    "ST", "SV", "SX", "SY", "SZ", "TC", "TD", "TF", "TG", "TH", "TJ", "TK", "TL", "TM", "TN", "TO",
+    "TR", "TT", "TV", "TW", "TZ", "UA", "UG", "UM", "US", "UY", "UZ", "VA", "VC", "VE", "VG", "VI",
+    "VN", "VU", "WF", "WS", "YE", "YT", "ZA", "ZM", "ZW",
+];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_cidr() {
+        let cidr = "10.0.0.0/8".parse::<Cidr>().unwrap();
+        assert_eq!("10.0.0.0".parse::<IpAddr>().unwrap(), cidr.addr());
+        assert_eq!(8, cidr.prefix_len());
+        assert_eq!("10.0.0.0/8", cidr.to_string());
+
+        let cidr = "2001:db8::/32".parse::<Cidr>().unwrap();
+        assert_eq!("2001:db8::/32", cidr.to_string());
+    }
+
+    #[test]
+    fn parse_host() {
+        assert_eq!("10.0.0.1/32", "10.0.0.1".parse::<Cidr>().unwrap().to_string());
+        assert_eq!("::1/128", "::1".parse::<Cidr>().unwrap().to_string());
+        assert_eq!("0.0.0.0/0", "0.0.0.0/0".parse::<Cidr>().unwrap().to_string());
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!("10.0.0.1/8".parse::<Cidr>().is_err());
+        assert!("10.0.0.0/33".parse::<Cidr>().is_err());
+        assert!("10.0.0.0/".parse::<Cidr>().is_err());
+        assert!("2001:db8::1/32".parse::<Cidr>().is_err());
+        assert!("example.com/8".parse::<Cidr>().is_err());
+    }
+
+    #[test]
+    fn range_to_cidrs() {
+        let cidrs = |a: &str, b: &str| -> Vec<String> {
+            range(a.parse().unwrap(), b.parse().unwrap()).unwrap().iter().map(Cidr::to_string).collect()
+        };
+
+        assert_eq!(vec!["10.0.0.0/24"], cidrs("10.0.0.0", "10.0.0.255"));
+        assert_eq!(vec!["10.0.0.1/32"], cidrs("10.0.0.1", "10.0.0.1"));
+        assert_eq!(vec!["10.0.0.1/32", "10.0.0.2/31", "10.0.0.4/32"], cidrs("10.0.0.1", "10.0.0.4"));
+        assert_eq!(vec!["0.0.0.0/0"], cidrs("0.0.0.0", "255.255.255.255"));
+        assert_eq!(vec!["::/0"], cidrs("::", "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff"));
+        assert_eq!(vec!["2001:db8::/127"], cidrs("2001:db8::", "2001:db8::1"));
+
+        assert!(range("10.0.0.2".parse().unwrap(), "10.0.0.1".parse().unwrap()).is_err());
+        assert!(range("10.0.0.1".parse().unwrap(), "::1".parse().unwrap()).is_err());
+    }
+
+    #[test]
+    fn checked_range() {
+        let (a, b) = ("10.0.0.1".parse().unwrap(), "10.0.0.9".parse().unwrap());
+
+        let range = Range::new(a, b).unwrap();
+        assert_eq!((a, b), (range.start(), range.end()));
+        assert_eq!("10.0.0.1-10.0.0.9", range.to_string());
+        assert_eq!(Err(Error::Invalid("invalid IP range 10.0.0.9-10.0.0.1".to_owned())), Range::new(b, a));
+    }
+
+    #[test]
+    fn aggregate_cidrs() {
+        let aggregate = |cidrs: &[&str]| -> Vec<String> {
+            super::aggregate(cidrs.iter().map(|c| c.parse().unwrap())).iter().map(Cidr::to_string).collect()
+        };
+
+        assert_eq!(vec!["10.0.0.0/23"], aggregate(&["10.0.1.0/24", "10.0.0.0/24"]));
+        assert_eq!(vec!["10.0.0.0/8"], aggregate(&["10.1.0.0/16", "10.0.0.0/8", "10.0.0.1"]));
+        assert_eq!(vec!["10.0.0.0/31", "10.0.0.2/32"], aggregate(&["10.0.0.0", "10.0.0.1", "10.0.0.2"]));
+        assert_eq!(vec!["10.0.0.0/24", "10.0.2.0/24"], aggregate(&["10.0.0.0/24", "10.0.2.0/24"]));
+        assert_eq!(vec!["10.0.0.0/24", "::/127"], aggregate(&["::1", "10.0.0.0/24", "::"]));
+        assert!(aggregate(&[]).is_empty());
+
+        let hosts = (0..=255).map(|n| format!("192.168.0.{}", n)).collect::<Vec<_>>();
+        let hosts = hosts.iter().map(String::as_str).collect::<Vec<_>>();
+        assert_eq!(vec!["192.168.0.0/24"], aggregate(&hosts));
+    }
+
+    #[test]
+    fn parse_mac() {
+        let mac = "00:1A:2b:3c:4d:5e".parse::<MacAddr>().unwrap();
+        assert_eq!([0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e], mac.octets());
+        assert_eq!("00:1a:2b:3c:4d:5e", mac.to_string());
+        assert_eq!(Ok(mac), "00-1a-2b-3c-4d-5e".parse());
+
+        assert!("00:1a:2b:3c:4d".parse::<MacAddr>().is_err());
+        assert!("00:1a:2b:3c:4d:5e:6f".parse::<MacAddr>().is_err());
+        assert!("00:1a:2b:3c:4d:zz".parse::<MacAddr>().is_err());
+    }
+
+    #[test]
+    fn parse_country() {
+        assert_eq!("US", "us".parse::<Country>().unwrap().as_str());
+        assert_eq!("DE", "DE".parse::<Country>().unwrap().to_string());
+        assert!("XX".parse::<Country>().is_err());
+        assert!("USA".parse::<Country>().is_err());
+    }
+
+    #[test]
+    fn parse_community() {
+        assert_eq!(Ok(Community::Standard(65000, 100)), "65000:100".parse());
+        assert_eq!(Ok(Community::Asn4(4200000000, 1)), "4200000000:1".parse());
+        assert_eq!(Ok(Community::Large(4200000000, 1, 2)), "4200000000:1:2".parse());
+        assert_eq!("65000:100", Community::Standard(65000, 100).to_string());
+        assert_eq!("4200000000:1:2", Community::Large(4200000000, 1, 2).to_string());
+        assert!("65000".parse::<Community>().is_err());
+        assert!("4294967296:1".parse::<Community>().is_err());
+        assert!("1:2:3:4".parse::<Community>().is_err());
+
+        assert_eq!(Ok(CommunityMatch::Literal(Community::Standard(65000, 1))), "65000:1".parse());
+        assert_eq!(Ok(CommunityMatch::Regex("^65000:.*$".to_owned())), "^65000:.*$".parse());
+        assert!("65000".parse::<CommunityMatch>().is_err());
+        assert_eq!("^65000:.*$", CommunityMatch::Regex("^65000:.*$".to_owned()).to_string());
+    }
+
+    #[test]
+    fn contains() {
+        let cidr = "10.1.0.0/16".parse::<Cidr>().unwrap();
+        assert!(cidr.contains(&"10.1.2.3".parse().unwrap()));
+        assert!(!cidr.contains(&"10.2.0.0".parse().unwrap()));
+        assert!(!cidr.contains(&"::a01:0".parse().unwrap()));
+    }
+}
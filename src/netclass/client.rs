@@ -0,0 +1,23 @@
+use serde::{Serialize, Deserialize};
+use crate::client::Client;
+use crate::Error;
+use super::NetworkClass;
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Wrapper<T> {
+    #[serde(rename = "networkClass")]
+    network_class: T,
+}
+
+impl Client {
+    pub fn get_network_class(&self) -> Result<NetworkClass, Error> {
+        let url = format!("{}/network_class/v202109beta1/network_class", self.endpoint());
+        Ok(self.get::<Wrapper<_>>(&url)?.network_class)
+    }
+
+    pub fn update_network_class(&self, nc: &NetworkClass) -> Result<NetworkClass, Error> {
+        let url  = format!("{}/network_class/v202109beta1/network_class", self.endpoint());
+        let body = Wrapper { network_class: nc };
+        Ok(self.put::<_, Wrapper<_>>(&url, &body)?.network_class)
+    }
+}
@@ -0,0 +1,41 @@
+use serde::{Serialize, Deserialize};
+use crate::net::Cidr;
+
+pub mod client;
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkClass {
+    #[serde(default)]
+    pub internal_ips:      Vec<Cidr>,
+    #[serde(default)]
+    pub internal_asns:     Vec<u32>,
+    #[serde(default)]
+    pub use_internal_ips:  bool,
+    #[serde(default)]
+    pub use_internal_asns: bool,
+    #[serde(default)]
+    pub cloud_subnets:     Vec<CloudSubnets>,
+    #[serde(default)]
+    pub our_networks:      Vec<Cidr>,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub struct CloudSubnets {
+    #[serde(rename = "type")]
+    pub kind:    CloudType,
+    #[serde(default)]
+    pub subnets: Vec<Cidr>,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, Debug)]
+pub enum CloudType {
+    #[serde(rename = "CLOUD_TYPE_AWS")]
+    Aws,
+    #[serde(rename = "CLOUD_TYPE_AZURE")]
+    Azure,
+    #[serde(rename = "CLOUD_TYPE_GCE")]
+    Gcp,
+    #[serde(rename = "CLOUD_TYPE_IBM")]
+    Ibm,
+}
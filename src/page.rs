@@ -0,0 +1,183 @@
+use std::future::Future;
+use std::vec::IntoIter;
+use futures::stream::{self, Stream, StreamExt};
+use crate::{AsyncClient, Client, Error};
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Cursor {
+    Offset(usize),
+    Token(String),
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next:  Option<Cursor>,
+}
+
+pub struct Pages<T, F> {
+    fetch: F,
+    next:  Option<Cursor>,
+    items: IntoIter<T>,
+}
+
+impl<T> Page<T> {
+    pub fn offset(items: Vec<T>, offset: usize, limit: usize) -> Self {
+        let next = match items.len() {
+            n if n > 0 && n >= limit => Some(Cursor::Offset(offset + n)),
+            _                        => None,
+        };
+        Self { items, next }
+    }
+
+    pub fn token(items: Vec<T>, token: Option<String>) -> Self {
+        let next = token.filter(|t| !t.is_empty()).map(Cursor::Token);
+        Self { items, next }
+    }
+}
+
+pub fn iter<T, F>(first: Cursor, fetch: F) -> Pages<T, F>
+    where F: FnMut(Cursor) -> Result<Page<T>, Error>
+{
+    Pages {
+        fetch,
+        next:  Some(first),
+        items: Vec::new().into_iter(),
+    }
+}
+
+pub fn stream<T, F, Fut>(first: Cursor, fetch: F) -> impl Stream<Item = Result<T, Error>>
+    where F: FnMut(Cursor) -> Fut,
+          Fut: Future<Output = Result<Page<T>, Error>>
+{
+    stream::unfold((Some(first), fetch), |(next, mut fetch)| async move {
+        let page = match fetch(next?).await {
+            Ok(Page{items, next}) => (items.into_iter().map(Ok).collect(), next),
+            Err(e)                => (vec![Err(e)], None),
+        };
+        Some((stream::iter(page.0), (page.1, fetch)))
+    }).flatten()
+}
+
+impl<T, F> Iterator for Pages<T, F> where F: FnMut(Cursor) -> Result<Page<T>, Error> {
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.items.next() {
+                return Some(Ok(item));
+            }
+
+            match (self.fetch)(self.next.take()?) {
+                Ok(page) => {
+                    self.items = page.items.into_iter();
+                    self.next  = page.next;
+                },
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+impl Client {
+    pub fn paginate<'a, T, F>(&'a self, first: Cursor, mut fetch: F) -> impl Iterator<Item = Result<T, Error>> + 'a
+        where F: FnMut(&Client, Cursor) -> Result<Page<T>, Error> + 'a,
+              T: 'a
+    {
+        iter(first, move |cursor| fetch(self, cursor))
+    }
+}
+
+impl AsyncClient {
+    pub fn paginate<T, F, Fut>(&self, first: Cursor, mut fetch: F) -> impl Stream<Item = Result<T, Error>>
+        where F: FnMut(AsyncClient, Cursor) -> Fut,
+              Fut: Future<Output = Result<Page<T>, Error>>
+    {
+        let client = self.clone();
+        stream(first, move |cursor| fetch(client.clone(), cursor))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+    use futures::executor::block_on_stream;
+    use futures::future::ready;
+    use super::*;
+
+    #[test]
+    fn offset_pages() {
+        let data  = (0..7).collect::<Vec<u32>>();
+        let pages = iter(Cursor::Offset(0), |cursor| match cursor {
+            Cursor::Offset(n) => Ok(Page::offset(data.iter().skip(n).take(3).copied().collect(), n, 3)),
+            Cursor::Token(_)  => unreachable!(),
+        });
+
+        assert_eq!(Ok(data.clone()), pages.collect::<Result<Vec<_>, _>>());
+    }
+
+    #[test]
+    fn token_pages() {
+        let pages = iter(Cursor::Token(String::new()), |cursor| match cursor {
+            Cursor::Token(t) if t.is_empty() => Ok(Page::token(vec![1, 2], Some("a".to_owned()))),
+            Cursor::Token(t) if t == "a"     => Ok(Page::token(vec![],     Some("b".to_owned()))),
+            Cursor::Token(t) if t == "b"     => Ok(Page::token(vec![3],    Some(String::new()))),
+            _                                => unreachable!(),
+        });
+
+        assert_eq!(Ok(vec![1, 2, 3]), pages.collect::<Result<Vec<_>, _>>());
+    }
+
+    #[test]
+    fn fetch_on_demand() {
+        let fetched = Cell::new(0);
+        let mut pages = iter(Cursor::Offset(0), |cursor| {
+            fetched.set(fetched.get() + 1);
+            match cursor {
+                Cursor::Offset(n) => Ok(Page::offset(vec![n; 2], n, 2)),
+                Cursor::Token(_)  => unreachable!(),
+            }
+        });
+
+        assert_eq!(0, fetched.get());
+        assert_eq!(Some(Ok(0)), pages.next());
+        assert_eq!(Some(Ok(0)), pages.next());
+        assert_eq!(1, fetched.get());
+        assert_eq!(Some(Ok(2)), pages.next());
+        assert_eq!(2, fetched.get());
+
+        drop(pages);
+        assert_eq!(2, fetched.get());
+    }
+
+    #[test]
+    fn stop_on_error() {
+        let pages = iter(Cursor::Offset(0), |cursor| match cursor {
+            Cursor::Offset(0) => Ok(Page::offset(vec![1], 0, 1)),
+            _                 => Err(Error::Status(503)),
+        });
+
+        assert_eq!(vec![Ok(1), Err(Error::Status(503))], pages.collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn stream_pages() {
+        let fetched = Cell::new(0);
+        let pages   = stream(Cursor::Offset(0), |cursor| {
+            fetched.set(fetched.get() + 1);
+            ready(match cursor {
+                Cursor::Offset(n) if n < 4 => Ok(Page::offset(vec![n, n + 1], n, 2)),
+                Cursor::Offset(_)          => Err(Error::Timeout),
+                Cursor::Token(_)           => unreachable!(),
+            })
+        });
+
+        let mut items = block_on_stream(Box::pin(pages));
+        assert_eq!(Some(Ok(0)), items.next());
+        assert_eq!(1, fetched.get());
+
+        let rest = items.collect::<Vec<_>>();
+        assert_eq!(vec![Ok(1), Ok(2), Ok(3), Err(Error::Timeout)], rest);
+        assert_eq!(3, fetched.get());
+    }
+}
@@ -0,0 +1,98 @@
+use serde::{Serialize, Deserialize};
+use crate::client::Client;
+use crate::Error;
+use super::{Agent, ResultsQuery, Test, TestResults, TestStatus, TraceQuery, Traceroute};
+
+impl Client {
+    pub fn get_synthetic_tests(&self) -> Result<Vec<Test>, Error> {
+        let url = format!("{}/synthetics/v202202/tests", self.endpoint());
+
+        #[derive(Serialize, Deserialize, Debug)]
+        struct Wrapper {
+            #[serde(default)]
+            tests: Vec<Test>,
+        }
+
+        Ok(self.get::<Wrapper>(&url)?.tests)
+    }
+
+    pub fn get_synthetic_test(&self, id: &str) -> Result<Test, Error> {
+        let url = format!("{}/synthetics/v202202/tests/{}", self.endpoint(), id);
+        Ok(self.get::<TestWrapper<_>>(&url)?.test)
+    }
+
+    pub fn create_synthetic_test(&self, test: &Test) -> Result<Test, Error> {
+        let url = format!("{}/synthetics/v202202/tests", self.endpoint());
+        Ok(self.post::<_, TestWrapper<_>>(&url, &TestWrapper { test })?.test)
+    }
+
+    pub fn update_synthetic_test(&self, test: &Test) -> Result<Test, Error> {
+        let url = format!("{}/synthetics/v202202/tests/{}", self.endpoint(), test.id);
+        Ok(self.put::<_, TestWrapper<_>>(&url, &TestWrapper { test })?.test)
+    }
+
+    pub fn delete_synthetic_test(&self, id: &str) -> Result<(), Error> {
+        let url = format!("{}/synthetics/v202202/tests/{}", self.endpoint(), id);
+        self.delete(&url)
+    }
+
+    pub fn set_synthetic_test_status(&self, id: &str, status: TestStatus) -> Result<(), Error> {
+        let url = format!("{}/synthetics/v202202/tests/{}/status", self.endpoint(), id);
+
+        #[derive(Serialize, Deserialize, Debug)]
+        struct Status<'a> {
+            id:     &'a str,
+            status: TestStatus,
+        }
+
+        #[derive(Serialize, Deserialize, Debug)]
+        struct Empty {}
+
+        self.put::<_, Empty>(&url, &Status { id, status }).map(drop)
+    }
+
+    pub fn get_synthetic_agents(&self) -> Result<Vec<Agent>, Error> {
+        let url = format!("{}/synthetics/v202202/agents", self.endpoint());
+
+        #[derive(Serialize, Deserialize, Debug)]
+        struct Wrapper {
+            #[serde(default)]
+            agents: Vec<Agent>,
+        }
+
+        Ok(self.get::<Wrapper>(&url)?.agents)
+    }
+
+    pub fn get_synthetic_agent(&self, id: &str) -> Result<Agent, Error> {
+        let url = format!("{}/synthetics/v202202/agents/{}", self.endpoint(), id);
+
+        #[derive(Serialize, Deserialize, Debug)]
+        struct Wrapper {
+            agent: Agent,
+        }
+
+        Ok(self.get::<Wrapper>(&url)?.agent)
+    }
+
+    pub fn get_synthetic_results(&self, q: &ResultsQuery) -> Result<Vec<TestResults>, Error> {
+        let url = format!("{}/synthetics/v202202/results", self.endpoint());
+
+        #[derive(Serialize, Deserialize, Debug)]
+        struct Wrapper {
+            #[serde(default)]
+            results: Vec<TestResults>,
+        }
+
+        Ok(self.post::<_, Wrapper>(&url, q)?.results)
+    }
+
+    pub fn get_synthetic_trace(&self, q: &TraceQuery) -> Result<Traceroute, Error> {
+        let url = format!("{}/synthetics/v202202/trace", self.endpoint());
+        self.post(&url, q)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct TestWrapper<T> {
+    test: T,
+}
@@ -0,0 +1,469 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+pub mod client;
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct Test {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id:       String,
+    pub name:     String,
+    #[serde(rename = "type")]
+    pub kind:     TestKind,
+    #[serde(default)]
+    pub status:   TestStatus,
+    pub settings: Settings,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum TestKind {
+    Ip,
+    Hostname,
+    Agent,
+    Dns,
+    DnsGrid,
+    NetworkGrid,
+    Url,
+    PageLoad,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, Debug, Default)]
+pub enum TestStatus {
+    #[default]
+    #[serde(rename = "TEST_STATUS_ACTIVE")]
+    Active,
+    #[serde(rename = "TEST_STATUS_PAUSED")]
+    Paused,
+    #[serde(rename = "TEST_STATUS_DELETED")]
+    Deleted,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Settings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ip:                    Option<IpTarget>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname:              Option<HostnameTarget>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent:                 Option<AgentTarget>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dns:                   Option<DnsTarget>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dns_grid:              Option<DnsTarget>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network_grid:          Option<IpTarget>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url:                   Option<UrlTarget>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_load:             Option<PageLoadTarget>,
+    #[serde(default)]
+    pub agent_ids:             Vec<String>,
+    #[serde(default)]
+    pub tasks:                 Vec<Task>,
+    #[serde(default)]
+    pub health_settings:       HealthSettings,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ping:                  Option<Ping>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace:                 Option<Trace>,
+    #[serde(default)]
+    pub period:                u32,
+    #[serde(default)]
+    pub family:                IpFamily,
+    #[serde(default)]
+    pub notification_channels: Vec<String>,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub struct IpTarget {
+    pub targets: Vec<IpAddr>,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub struct HostnameTarget {
+    pub target: String,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentTarget {
+    pub target:       String,
+    #[serde(default)]
+    pub use_local_ip: bool,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DnsTarget {
+    pub target:      String,
+    #[serde(default)]
+    pub timeout:     u32,
+    #[serde(default)]
+    pub record_type: DnsRecord,
+    #[serde(default)]
+    pub servers:     Vec<IpAddr>,
+    #[serde(default)]
+    pub port:        u16,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, Debug, Default)]
+pub enum DnsRecord {
+    #[default]
+    #[serde(rename = "DNS_RECORD_A")]
+    A,
+    #[serde(rename = "DNS_RECORD_AAAA")]
+    Aaaa,
+    #[serde(rename = "DNS_RECORD_CNAME")]
+    Cname,
+    #[serde(rename = "DNS_RECORD_DNAME")]
+    Dname,
+    #[serde(rename = "DNS_RECORD_NS")]
+    Ns,
+    #[serde(rename = "DNS_RECORD_MX")]
+    Mx,
+    #[serde(rename = "DNS_RECORD_PTR")]
+    Ptr,
+    #[serde(rename = "DNS_RECORD_SOA")]
+    Soa,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlTarget {
+    pub target:            String,
+    #[serde(default)]
+    pub timeout:           u32,
+    #[serde(default)]
+    pub method:            String,
+    #[serde(default)]
+    pub headers:           HashMap<String, String>,
+    #[serde(default)]
+    pub body:              String,
+    #[serde(default)]
+    pub ignore_tls_errors: bool,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PageLoadTarget {
+    pub target:            String,
+    #[serde(default)]
+    pub timeout:           u32,
+    #[serde(default)]
+    pub headers:           HashMap<String, String>,
+    #[serde(default)]
+    pub ignore_tls_errors: bool,
+    #[serde(default)]
+    pub css_selectors:     HashMap<String, String>,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum Task {
+    Ping,
+    Traceroute,
+    Http,
+    PageLoad,
+    Dns,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HealthSettings {
+    pub latency_critical:      f64,
+    pub latency_warning:       f64,
+    pub packet_loss_critical:  f64,
+    pub packet_loss_warning:   f64,
+    pub jitter_critical:       f64,
+    pub jitter_warning:        f64,
+    pub http_latency_critical: f64,
+    pub http_latency_warning:  f64,
+    pub http_valid_codes:      Vec<u32>,
+    pub dns_valid_codes:       Vec<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activation:            Option<Activation>,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Activation {
+    pub grace_period: String,
+    pub time_unit:    String,
+    pub time_window:  String,
+    pub times:        String,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Ping {
+    pub count:    u32,
+    pub protocol: String,
+    pub port:     u16,
+    pub timeout:  u32,
+    pub delay:    u32,
+    pub dscp:     u32,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Trace {
+    pub count:    u32,
+    pub protocol: String,
+    pub port:     u16,
+    pub timeout:  u32,
+    pub limit:    u32,
+    pub delay:    u32,
+    pub dscp:     u32,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, Debug, Default)]
+pub enum IpFamily {
+    #[default]
+    #[serde(rename = "IP_FAMILY_DUAL")]
+    Dual,
+    #[serde(rename = "IP_FAMILY_V4")]
+    V4,
+    #[serde(rename = "IP_FAMILY_V6")]
+    V6,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Agent {
+    pub id:             String,
+    pub site_name:      String,
+    pub status:         AgentStatus,
+    pub alias:          String,
+    #[serde(rename = "type")]
+    pub kind:           AgentKind,
+    pub os:             String,
+    pub ip:             String,
+    pub lat:            f64,
+    pub long:           f64,
+    pub asn:            u32,
+    pub country:        String,
+    pub region:         String,
+    pub city:           String,
+    pub version:        String,
+    pub local_ip:       String,
+    pub cloud_region:   String,
+    pub cloud_provider: String,
+    pub test_ids:       Vec<String>,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, Debug, Default)]
+pub enum AgentStatus {
+    #[default]
+    #[serde(rename = "AGENT_STATUS_OK")]
+    Ok,
+    #[serde(rename = "AGENT_STATUS_WAIT")]
+    Wait,
+    #[serde(rename = "AGENT_STATUS_DELETED")]
+    Deleted,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentKind {
+    #[default]
+    Global,
+    Private,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ResultsQuery {
+    pub ids:        Vec<String>,
+    pub start_time: DateTime<Utc>,
+    pub end_time:   DateTime<Utc>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub agent_ids:  Vec<String>,
+    pub aggregate:  bool,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TestResults {
+    pub test_id: String,
+    pub time:    DateTime<Utc>,
+    #[serde(default)]
+    pub health:  Health,
+    #[serde(default)]
+    pub agents:  Vec<AgentResults>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentResults {
+    pub agent_id: String,
+    #[serde(default)]
+    pub health:   Health,
+    #[serde(default)]
+    pub tasks:    Vec<TaskResults>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct TaskResults {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ping:   Option<PingResults>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http:   Option<HttpResults>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dns:    Option<DnsResults>,
+    pub health: Health,
+}
+
+// latency and jitter values are reported in microseconds
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PingResults {
+    pub target:      String,
+    pub packet_loss: PacketLoss,
+    pub latency:     Metric,
+    pub jitter:      Metric,
+    pub dst_ip:      String,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HttpResults {
+    pub target:   String,
+    pub latency:  Metric,
+    pub response: HttpResponse,
+    pub dst_ip:   String,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct HttpResponse {
+    pub status: u32,
+    pub size:   u64,
+    pub data:   String,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct DnsResults {
+    pub target:   String,
+    pub server:   String,
+    pub latency:  Metric,
+    pub response: DnsResponse,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct DnsResponse {
+    pub status: u32,
+    pub data:   String,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct PacketLoss {
+    pub current: f64,
+    pub health:  Health,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Metric {
+    pub current:        f64,
+    pub rolling_avg:    f64,
+    pub rolling_stddev: f64,
+    pub health:         Health,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Health {
+    Healthy,
+    Warning,
+    Critical,
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceQuery {
+    pub id:         String,
+    pub start_time: DateTime<Utc>,
+    pub end_time:   DateTime<Utc>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub agent_ids:  Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub target_ips: Vec<IpAddr>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Traceroute {
+    pub nodes: HashMap<String, Node>,
+    pub paths: Vec<Path>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Node {
+    pub ip:        String,
+    pub asn:       u32,
+    pub as_name:   String,
+    pub location:  Location,
+    pub dns_name:  String,
+    pub device_id: String,
+    pub site_id:   String,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Location {
+    pub latitude:  f64,
+    pub longitude: f64,
+    pub country:   String,
+    pub region:    String,
+    pub city:      String,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Path {
+    pub agent_id:           String,
+    pub target_ip:          String,
+    #[serde(default)]
+    pub hop_count:          HopCount,
+    #[serde(default)]
+    pub max_as_path_length: u32,
+    #[serde(default)]
+    pub traces:             Vec<PathTrace>,
+    pub time:               DateTime<Utc>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct HopCount {
+    pub average: f64,
+    pub min:     u32,
+    pub max:     u32,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PathTrace {
+    pub as_path:     Vec<u32>,
+    pub is_complete: bool,
+    pub hops:        Vec<Hop>,
+}
+
+// hop latency is reported in microseconds
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Hop {
+    pub latency: f64,
+    pub node_id: String,
+}
@@ -0,0 +1,368 @@
+use std::collections::BTreeSet;
+use std::convert::TryFrom;
+use std::net::IpAddr;
+use crate::{Client, Error};
+use crate::core::Populator;
+use crate::net::{self, Cidr, CommunityMatch};
+use super::Rules;
+use super::reconcile::normalize;
+
+#[derive(Clone, Eq, PartialEq, Default, Debug)]
+pub struct Report {
+    pub conflicts:  Vec<Pair>,
+    pub shadowed:   Vec<Shadow>,
+    pub duplicates: Vec<Pair>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Entry {
+    pub id:    u64,
+    pub value: String,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Pair {
+    pub first:  Entry,
+    pub second: Entry,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Shadow {
+    pub rule: Entry,
+    pub by:   Entry,
+}
+
+impl Client {
+    pub fn analyze_populators(&self, column: &str) -> Result<Report, Error> {
+        analyze(&self.get_populators(column)?)
+    }
+}
+
+pub fn analyze(populators: &[Populator]) -> Result<Report, Error> {
+    let sets = populators.iter().map(|p| {
+        let rules = normalize(Rules::try_from(p)?);
+        Ok((Entry { id: p.id, value: p.value.clone() }, Set::try_from(&rules)?))
+    }).collect::<Result<Vec<_>, Error>>()?;
+
+    let mut report = Report::default();
+
+    for (i, (a, x)) in sets.iter().enumerate() {
+        for (b, y) in &sets[i + 1..] {
+            let pair = || Pair { first: a.clone(), second: b.clone() };
+
+            if x == y {
+                match a.value == b.value {
+                    true  => report.duplicates.push(pair()),
+                    false => report.conflicts.push(pair()),
+                }
+                continue;
+            }
+
+            if !x.overlaps(y) {
+                continue;
+            }
+
+            if a.value != b.value {
+                report.conflicts.push(pair());
+            }
+
+            if x.within(y) {
+                report.shadowed.push(Shadow { rule: a.clone(), by: b.clone() });
+            } else if y.within(x) {
+                report.shadowed.push(Shadow { rule: b.clone(), by: a.clone() });
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+impl Report {
+    pub fn is_empty(&self) -> bool {
+        self.conflicts.is_empty() && self.shadowed.is_empty() && self.duplicates.is_empty()
+    }
+}
+
+// the set of flows matched by one populator, where None matches any value
+//
+// AS path and community regexes can't be compared, so fields holding
+// them are assumed to overlap and show up as possible conflicts
+#[derive(Eq, PartialEq, Debug)]
+struct Set {
+    direction: u8,
+    ranges:    Vec<Option<Vec<Interval>>>,
+    strings:   Vec<Option<BTreeSet<String>>>,
+    patterns:  Vec<Option<(bool, BTreeSet<String>)>>,
+    tcp_flags: u16,
+}
+
+// (v6, lo, hi), with numbers and IPv4 addresses in the same space
+type Interval = (bool, u128, u128);
+
+impl Set {
+    fn overlaps(&self, other: &Set) -> bool {
+        self.direction & other.direction != 0
+            && self.ranges.iter().zip(&other.ranges).all(|pair| match pair {
+                (Some(a), Some(b)) => a.iter().any(|x| b.iter().any(|y| x.0 == y.0 && x.1 <= y.2 && y.1 <= x.2)),
+                _                  => true,
+            })
+            && self.strings.iter().zip(&other.strings).all(|pair| match pair {
+                (Some(a), Some(b)) => !a.is_disjoint(b),
+                _                  => true,
+            })
+            && self.patterns.iter().zip(&other.patterns).all(|pair| match pair {
+                (Some((x, a)), Some((y, b))) => *x || *y || !a.is_disjoint(b),
+                _                            => true,
+            })
+    }
+
+    fn within(&self, other: &Set) -> bool {
+        self.direction & !other.direction == 0
+            && self.tcp_flags & other.tcp_flags == other.tcp_flags
+            && self.ranges.iter().zip(&other.ranges).all(|pair| match pair {
+                (_, None)          => true,
+                (None, Some(_))    => false,
+                (Some(a), Some(b)) => covered(a, b),
+            })
+            && self.strings.iter().zip(&other.strings).all(|pair| match pair {
+                (_, None)          => true,
+                (None, Some(_))    => false,
+                (Some(a), Some(b)) => a.is_subset(b),
+            })
+            && self.patterns.iter().zip(&other.patterns).all(|pair| match pair {
+                (_, None)                    => true,
+                (None, Some(_))              => false,
+                (Some((_, a)), Some((_, b))) => a.is_subset(b),
+            })
+    }
+}
+
+impl TryFrom<&Rules> for Set {
+    type Error = Error;
+
+    fn try_from(r: &Rules) -> Result<Self, Self::Error> {
+        let direction = match r.direction.as_deref() {
+            Some("src") => 1,
+            Some("dst") => 2,
+            _           => 3,
+        };
+
+        let protocol = r.protocol.iter().map(u64::to_string).collect::<Vec<_>>();
+
+        let ranges = vec![
+            ranges(&r.addr, address)?,
+            ranges(&r.next_hop, address)?,
+            ranges(&r.port, number)?,
+            ranges(&protocol, number)?,
+            ranges(&r.asn, number)?,
+            ranges(&r.vlans, number)?,
+            ranges(&r.nexthop_asn, number)?,
+        ];
+
+        let strings = [
+            &r.lasthop_as_name,
+            &r.nexthop_as_name,
+            &r.mac,
+            &r.country,
+            &r.site,
+            &r.device_type,
+            &r.interface_name,
+            &r.device_name,
+        ].iter().map(|vec| match vec.is_empty() {
+            true  => None,
+            false => Some(vec.iter().map(|s| s.to_lowercase()).collect()),
+        }).collect();
+
+        // every AS path is a regex, communities only when not a literal
+        let communities = r.bgp_community.iter().map(|s| s.trim().parse()).collect::<Result<Vec<CommunityMatch>, _>>()?;
+        let regex       = communities.iter().any(|c| matches!(c, CommunityMatch::Regex(..)));
+        let communities = communities.iter().map(CommunityMatch::to_string).collect();
+        let aspath      = r.bgp_aspath.iter().map(|s| s.trim().to_owned()).collect();
+
+        let patterns = vec![
+            (!r.bgp_aspath.is_empty()).then_some((true, aspath)),
+            (!r.bgp_community.is_empty()).then_some((regex, communities)),
+        ];
+
+        Ok(Set {
+            direction,
+            ranges,
+            strings,
+            patterns,
+            tcp_flags: r.tcp_flags.unwrap_or(0),
+        })
+    }
+}
+
+fn ranges(vec: &[String], parse: fn(&str) -> Result<Interval, Error>) -> Result<Option<Vec<Interval>>, Error> {
+    match vec.is_empty() {
+        true  => Ok(None),
+        false => vec.iter().map(|s| parse(s.trim())).collect::<Result<_, _>>().map(Some),
+    }
+}
+
+fn address(s: &str) -> Result<Interval, Error> {
+    let invalid = || Error::Invalid(format!("invalid address {}", s));
+    let key     = |ip: IpAddr| match ip {
+        IpAddr::V4(ip) => u128::from(u32::from(ip)),
+        IpAddr::V6(ip) => u128::from(ip),
+    };
+
+    match s.split_once('-') {
+        Some((start, end)) => {
+            let start = start.trim().parse().map_err(|_| invalid())?;
+            let end   = end.trim().parse().map_err(|_| invalid())?;
+            let range = net::Range::new(start, end)?;
+            Ok((start.is_ipv6(), key(range.start()), key(range.end())))
+        },
+        None => {
+            let cidr  = s.parse::<Cidr>()?;
+            let width = if cidr.addr().is_ipv4() { 32 } else { 128 };
+            let size  = width - u32::from(cidr.prefix_len());
+            let lo    = key(cidr.addr());
+            Ok((cidr.addr().is_ipv6(), lo, lo + (1u128.checked_shl(size).unwrap_or(0).wrapping_sub(1))))
+        },
+    }
+}
+
+fn number(s: &str) -> Result<Interval, Error> {
+    let invalid = || Error::Invalid(format!("invalid number {}", s));
+    let parse   = |s: &str| s.trim().parse::<u128>().map_err(|_| invalid());
+    match s.split_once('-') {
+        Some((lo, hi)) => Ok((false, parse(lo)?, parse(hi)?)),
+        None           => parse(s).map(|n| (false, n, n)),
+    }
+}
+
+// whether every interval in a is covered by the union of intervals in b
+fn covered(a: &[Interval], b: &[Interval]) -> bool {
+    let mut sorted = b.to_vec();
+    sorted.sort_unstable();
+
+    let mut merged = Vec::<Interval>::with_capacity(sorted.len());
+    for (v6, lo, hi) in sorted {
+        match merged.last_mut() {
+            Some(last) if last.0 == v6 && lo <= last.2.saturating_add(1) => last.2 = last.2.max(hi),
+            _                                                           => merged.push((v6, lo, hi)),
+        }
+    }
+
+    a.iter().all(|&(v6, lo, hi)| merged.iter().any(|m| m.0 == v6 && m.1 <= lo && hi <= m.2))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn analyze_conflicts() {
+        let populators = vec![
+            populator(1, "web",   "10.0.0.0/24", "443"),
+            populator(2, "api",   "10.0.0.128/25", "443"),
+            populator(3, "ssh",   "10.0.0.0/24", "22"),
+            populator(4, "other", "10.0.1.0/24", "443"),
+            populator(5, "admin", "10.0.0.0/24", "22"),
+        ];
+
+        let report = analyze(&populators).unwrap();
+
+        assert_eq!(vec![pair(1, "web", 2, "api"), pair(3, "ssh", 5, "admin")], report.conflicts);
+        assert_eq!(vec![Shadow { rule: entry(2, "api"), by: entry(1, "web") }], report.shadowed);
+        assert!(report.duplicates.is_empty());
+    }
+
+    #[test]
+    fn analyze_duplicates() {
+        let populators = vec![
+            populator(1, "web", "10.0.0.1/32", "443, 80"),
+            populator(2, "web", "10.0.0.1", "80,443"),
+            populator(3, "web", "10.0.0.0-10.0.0.255", "1-1024"),
+        ];
+
+        let report = analyze(&populators).unwrap();
+
+        assert_eq!(vec![pair(1, "web", 2, "web")], report.duplicates);
+        assert_eq!(vec![
+            Shadow { rule: entry(1, "web"), by: entry(3, "web") },
+            Shadow { rule: entry(2, "web"), by: entry(3, "web") },
+        ], report.shadowed);
+        assert!(report.conflicts.is_empty());
+    }
+
+    #[test]
+    fn analyze_direction() {
+        let mut src = populator(1, "a", "10.0.0.0/24", "");
+        let mut dst = populator(2, "b", "10.0.0.0/24", "");
+        src.direction = "src".to_owned();
+        dst.direction = "dst".to_owned();
+
+        let any = populator(3, "c", "", "");
+
+        let report = analyze(&[src, dst, any]).unwrap();
+
+        assert_eq!(vec![pair(1, "a", 3, "c"), pair(2, "b", 3, "c")], report.conflicts);
+        assert_eq!(2, report.shadowed.len());
+        assert!(report.duplicates.is_empty());
+    }
+
+    #[test]
+    fn address_ranges() {
+        assert_eq!(address("10.0.0.0/31").unwrap().2 - address("10.0.0.0/31").unwrap().1, 1);
+        assert_eq!(address("::/0").unwrap(), (true, 0, u128::MAX));
+        assert_eq!(address("10.0.0.1-10.0.0.9").unwrap(), (false, 0x0a000001, 0x0a000009));
+        assert!(address("10.0.0.9-10.0.0.1").is_err());
+        assert!(address("10.0.0.1-::1").is_err());
+        assert!(covered(&[(false, 0, 10)], &[(false, 0, 4), (false, 5, 10)]));
+        assert!(!covered(&[(false, 0, 10)], &[(false, 0, 4), (false, 6, 10)]));
+        assert!(!covered(&[(false, 0, 10)], &[(true, 0, 10)]));
+    }
+
+    #[test]
+    fn analyze_families() {
+        let populators = vec![
+            populator(1, "v4", "10.0.0.0/8", ""),
+            populator(2, "v6", "::ffff:10.0.0.0/104", ""),
+        ];
+
+        assert!(analyze(&populators).unwrap().is_empty());
+    }
+
+    #[test]
+    fn analyze_patterns() {
+        let community = |id, value: &str, community: &str| Populator {
+            bgp_community: community.to_owned(),
+            ..populator(id, value, "", "")
+        };
+        let aspath = |id, value: &str, aspath: &str| Populator {
+            bgp_aspath: aspath.to_owned(),
+            ..populator(id, value, "", "")
+        };
+
+        let report = analyze(&[community(1, "a", "65000:1"), community(2, "b", "65000:2")]).unwrap();
+        assert!(report.is_empty());
+
+        let report = analyze(&[community(1, "a", "65000:1"), community(2, "b", "^65000:.*$")]).unwrap();
+        assert_eq!(vec![pair(1, "a", 2, "b")], report.conflicts);
+
+        let report = analyze(&[aspath(1, "a", "^64512_"), aspath(2, "b", "_64513$")]).unwrap();
+        assert_eq!(vec![pair(1, "a", 2, "b")], report.conflicts);
+    }
+
+    fn populator(id: u64, value: &str, addr: &str, port: &str) -> Populator {
+        Populator {
+            id,
+            value: value.to_owned(),
+            addr:  addr.to_owned(),
+            port:  port.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    fn entry(id: u64, value: &str) -> Entry {
+        Entry { id, value: value.to_owned() }
+    }
+
+    fn pair(a: u64, x: &str, b: u64, y: &str) -> Pair {
+        Pair { first: entry(a, x), second: entry(b, y) }
+    }
+}
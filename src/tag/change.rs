@@ -1,5 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+use std::fmt::{self, Display};
+use std::mem::replace;
 use std::net::IpAddr;
+use std::str::FromStr;
+use crate::Error;
+use crate::net::{self, Cidr, CommunityMatch, Country, MacAddr};
 use Rules::*;
 
 #[derive(Eq, PartialEq, Debug)]
@@ -12,10 +18,19 @@ pub enum Change {
 pub struct Upsert(String, Values);
 
 #[derive(Eq, PartialEq, Debug)]
-pub struct Delete(String, String);
+pub struct Delete(String, Vec<String>);
 
 #[derive(Eq, PartialEq, Debug)]
-pub struct Values(HashMap<String, Rules>);
+pub struct Batch {
+    column:      String,
+    changes:     Vec<Change>,
+    replace_all: bool,
+    complete:    bool,
+    ttl_minutes: u32,
+}
+
+#[derive(Eq, PartialEq, Debug)]
+pub struct Values(HashMap<String, Vec<Rules>>);
 
 #[derive(Eq, PartialEq, Debug)]
 pub enum Rules {
@@ -24,20 +39,148 @@ pub enum Rules {
     Empty,
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[derive(Clone, Eq, PartialEq, Debug)]
 pub enum Rule {
+    Direction(Direction),
     IP(IpAddr),
+    Prefix(Cidr),
+    Range(net::Range),
     Port(u16),
- }
+    PortRange(u16, u16),
+    Protocol(u8),
+    Asn(u32),
+    Vlan(u16),
+    LastHopAsName(String),
+    NextHopAsn(u32),
+    NextHopAsName(String),
+    AsPath(String),
+    Community(CommunityMatch),
+    TcpFlags(u16),
+    Mac(MacAddr),
+    Country(Country),
+    Site(String),
+    DeviceType(String),
+    DeviceName(String),
+    InterfaceName(String),
+    NextHop(IpAddr),
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Direction {
+    Src,
+    Dst,
+    Either,
+}
 
 pub fn upsert(column: &str) -> Upsert {
     Upsert(column.to_string(), Values(HashMap::new()))
 }
 
+pub fn delete(column: &str) -> Delete {
+    Delete(column.to_string(), Vec::new())
+}
+
+pub fn batch(column: &str) -> Batch {
+    Batch {
+        column:      column.to_string(),
+        changes:     Vec::new(),
+        replace_all: false,
+        complete:    true,
+        ttl_minutes: 0,
+    }
+}
+
 impl Upsert {
     pub fn value(&mut self, value: &str) -> &mut Rules {
         let Upsert(_, Values(map)) = self;
-        map.entry(value.to_string()).or_insert(Empty)
+        let sets = map.entry(value.to_string()).or_default();
+        if sets.is_empty() {
+            sets.push(Empty);
+        }
+        let last = sets.len() - 1;
+        &mut sets[last]
+    }
+
+    // adds another set of criteria for the value, any of which may match
+    pub fn or_value(&mut self, value: &str) -> &mut Rules {
+        let Upsert(_, Values(map)) = self;
+        let sets = map.entry(value.to_string()).or_default();
+        sets.push(Empty);
+        let last = sets.len() - 1;
+        &mut sets[last]
+    }
+}
+
+impl Delete {
+    pub fn value(&mut self, value: &str) -> &mut Self {
+        if !self.1.iter().any(|v| v == value) {
+            self.1.push(value.to_string());
+        }
+        self
+    }
+}
+
+impl Batch {
+    pub fn change<T: Into<Change>>(&mut self, change: T) -> &mut Self {
+        self.changes.push(change.into());
+        self
+    }
+
+    pub fn replace_all(&mut self, replace_all: bool) -> &mut Self {
+        self.replace_all = replace_all;
+        self
+    }
+
+    pub fn complete(&mut self, complete: bool) -> &mut Self {
+        self.complete = complete;
+        self
+    }
+
+    pub fn ttl_minutes(&mut self, ttl_minutes: u32) -> &mut Self {
+        self.ttl_minutes = ttl_minutes;
+        self
+    }
+
+    pub fn build(self) -> Result<(String, super::Request), Error> {
+        let mut values = BTreeMap::new();
+
+        for change in self.changes {
+            let (column, entries) = match change {
+                Change::Upsert(Upsert(column, Values(map))) => {
+                    let map = map.into_iter().filter(|(_, sets)| sets.iter().any(|rules| !rules.is_empty()));
+                    (column, map.map(|(value, sets)| (value, Some(sets))).collect::<Vec<_>>())
+                },
+                Change::Delete(Delete(column, vec)) => {
+                    (column, vec.into_iter().map(|value| (value, None)).collect())
+                },
+            };
+
+            if column != self.column {
+                let msg = format!("change for {} in batch for {}", column, self.column);
+                return Err(Error::Invalid(msg));
+            }
+
+            values.extend(entries);
+        }
+
+        let mut upserts = Vec::new();
+        let mut deletes = Vec::new();
+
+        for (value, entry) in values {
+            match entry {
+                Some(sets) => upserts.extend(collect(value, sets)),
+                None       => deletes.push(super::Delete{value}),
+            }
+        }
+
+        Ok((self.column, super::Request {
+            replace_all: self.replace_all,
+            complete:    self.complete,
+            ttl_minutes: self.ttl_minutes,
+            upserts,
+            deletes,
+            guid:        None,
+        }))
     }
 }
 
@@ -48,10 +191,10 @@ impl Rules {
     }
 
     pub fn and(&mut self, rule: Rule) -> &mut Self {
-        match self {
-            One(one) => *self = All(vec![*one, rule]),
-            All(vec) => vec.push(rule),
-            Empty    => *self = All(vec![rule]),
+        match replace(self, Empty) {
+            One(one)     => *self = All(vec![one, rule]),
+            All(mut vec) => { vec.push(rule); *self = All(vec) },
+            Empty        => *self = All(vec![rule]),
         }
         self
     }
@@ -59,6 +202,31 @@ impl Rules {
     pub fn is_empty(&self) -> bool {
         *self == Empty
     }
+
+    fn into_vec(self) -> Vec<Rule> {
+        match self {
+            One(rule)  => vec![rule],
+            All(rules) => rules,
+            Empty      => Vec::new(),
+        }
+    }
+}
+
+impl Rule {
+    pub fn prefix(s: &str) -> Result<Self, Error> {
+        Ok(Rule::Prefix(s.parse()?))
+    }
+
+    pub fn range(start: IpAddr, end: IpAddr) -> Result<Self, Error> {
+        Ok(Rule::Range(net::Range::new(start, end)?))
+    }
+
+    pub fn port_range(lo: u16, hi: u16) -> Result<Self, Error> {
+        match lo <= hi {
+            true  => Ok(Rule::PortRange(lo, hi)),
+            false => Err(Error::Invalid(format!("invalid port range {}-{}", lo, hi))),
+        }
+    }
 }
 
 impl<T: Into<Values>> From<(&str, T)> for Upsert {
@@ -70,77 +238,332 @@ impl<T: Into<Values>> From<(&str, T)> for Upsert {
 impl<T: Into<Rules>> From<(&str, T)> for Values {
     fn from((value, rules): (&str, T)) -> Self {
         let mut map = HashMap::new();
-        map.insert(value.to_owned(), rules.into());
+        map.insert(value.to_owned(), vec![rules.into()]);
         Values(map)
     }
 }
 
-impl<T: Into<Rules> + Copy> From<&[(&str, T)]> for Values {
+impl<T: Into<Rules> + Clone> From<&[(&str, T)]> for Values {
     fn from(values: &[(&str, T)]) -> Self {
-        Values(values.iter().map(|&(value, rules)| {
-            (value.to_owned(), rules.into())
+        Values(values.iter().map(|(value, rules)| {
+            (value.to_string(), vec![rules.clone().into()])
         }).collect())
     }
 }
 
+impl From<Upsert> for Change {
+    fn from(upsert: Upsert) -> Self {
+        Change::Upsert(upsert)
+    }
+}
+
+impl From<Delete> for Change {
+    fn from(delete: Delete) -> Self {
+        Change::Delete(delete)
+    }
+}
+
 impl From<Rule> for Rules {
     fn from(rule: Rule) -> Self {
         Rules::One(rule)
     }
 }
 
-impl Into<(String, Vec<super::Upsert>)> for Upsert {
-    fn into(self) -> (String, Vec<super::Upsert>) {
-        (self.0, self.1.into())
+impl From<Upsert> for (String, Vec<super::Upsert>) {
+    fn from(upsert: Upsert) -> Self {
+        (upsert.0, upsert.1.into())
     }
 }
 
-impl Into<Vec<super::Upsert>> for Values {
-    fn into(self) -> Vec<super::Upsert> {
-        self.0.into_iter().flat_map(|(value, rules)| {
-            collect(value, rules)
-        }).collect()
+impl From<Delete> for (String, Vec<super::Delete>) {
+    fn from(delete: Delete) -> Self {
+        (delete.0, delete.1.into_iter().map(|value| super::Delete{value}).collect())
     }
 }
 
-fn collect(value: String, rules: Rules) -> Vec<super::Upsert> {
-    let mut vec = Vec::new();
-    match rules {
-        One(rule)  => vec.push(super::Upsert::Small(one(value, rule))),
-        All(rules) => vec.push(super::Upsert::Large(all(value, rules))),
-        Empty      => (),
+impl From<Values> for Vec<super::Upsert> {
+    fn from(values: Values) -> Self {
+        values.0.into_iter().flat_map(|(value, sets)| {
+            collect(value, sets)
+        }).collect()
     }
-    vec
 }
 
-fn one(value: String, src: Rule) -> super::Small {
-    super::Small{value, criteria: (src.into(),)}
+fn collect(value: String, sets: Vec<Rules>) -> Option<super::Upsert> {
+    let mut sets = sets.into_iter().filter(|rules| !rules.is_empty()).collect::<Vec<_>>();
+    match sets.len() {
+        0 => None,
+        1 => match sets.remove(0) {
+            One(rule)  => match super::Rule::try_from(rule.clone()) {
+                Ok(small) => Some(super::Upsert::Small(super::Small{value, criteria: (small,)})),
+                Err(_)    => Some(super::Upsert::Large(all(value, vec![rule]))),
+            },
+            All(rules) => Some(super::Upsert::Large(all(value, rules))),
+            Empty      => None,
+        },
+        _ => Some(super::Upsert::Large(super::Large {
+            criteria: sets.into_iter().map(|rules| criteria(rules.into_vec())).collect(),
+            value,
+        })),
+    }
 }
 
 fn all(value: String, src: Vec<Rule>) -> super::Large {
+    super::Large{value, criteria: vec![criteria(src)]}
+}
+
+fn criteria(src: Vec<Rule>) -> super::Rules {
     let mut rules = super::Rules::default();
+    let mut addrs = Vec::new();
     for rule in src {
         match rule {
-            Rule::IP(ip)     => rules.addr.push(ip.to_string()),
-            Rule::Port(port) => rules.port.push(port.to_string()),
+            Rule::Direction(d)        => rules.direction = Some(d.to_string()),
+            Rule::IP(ip)              => addrs.push(Cidr::host(ip)),
+            Rule::Prefix(cidr)        => addrs.push(cidr),
+            Rule::Range(range)        => addrs.extend(range.cidrs()),
+            Rule::Port(port)          => rules.port.push(port.to_string()),
+            Rule::PortRange(lo, hi)   => rules.port.push(format!("{}-{}", lo, hi)),
+            Rule::Protocol(proto)     => rules.protocol.push(proto.into()),
+            Rule::Asn(asn)            => rules.asn.push(asn.to_string()),
+            Rule::Vlan(vlan)          => rules.vlans.push(vlan.to_string()),
+            Rule::LastHopAsName(name) => rules.lasthop_as_name.push(name),
+            Rule::NextHopAsn(asn)     => rules.nexthop_asn.push(asn.to_string()),
+            Rule::NextHopAsName(name) => rules.nexthop_as_name.push(name),
+            Rule::AsPath(path)        => rules.bgp_aspath.push(path),
+            Rule::Community(c)        => rules.bgp_community.push(c.to_string()),
+            Rule::TcpFlags(flags)     => rules.tcp_flags = Some(rules.tcp_flags.unwrap_or(0) | flags),
+            Rule::Mac(mac)            => rules.mac.push(mac.to_string()),
+            Rule::Country(country)    => rules.country.push(country.to_string()),
+            Rule::Site(site)          => rules.site.push(site),
+            Rule::DeviceType(kind)    => rules.device_type.push(kind),
+            Rule::DeviceName(name)    => rules.device_name.push(name),
+            Rule::InterfaceName(name) => rules.interface_name.push(name),
+            Rule::NextHop(ip)         => rules.next_hop.push(ip.to_string()),
         }
     }
-    super::Large{value, criteria: vec![rules]}
+    rules.addr.extend(net::aggregate(addrs).into_iter().map(addr));
+    rules
+}
+
+fn addr(cidr: Cidr) -> String {
+    match cidr.is_host() {
+        true  => cidr.addr().to_string(),
+        false => cidr.to_string(),
+    }
 }
 
-impl Into<super::Rule> for Rule {
-    fn into(self) -> super::Rule {
+// a small upsert holds a single address, so only a range
+// covered by one prefix can be expressed as a single rule
+impl TryFrom<Rule> for super::Rule {
+    type Error = Error;
+
+    fn try_from(rule: Rule) -> Result<Self, Self::Error> {
         let mut rules = super::Rule::default();
-        match self {
-            Rule::IP(ip)     => rules.addr = Some((ip.to_string(),)),
-            Rule::Port(port) => rules.port = Some((port.to_string(),)),
+        match rule {
+            Rule::Direction(d)        => rules.direction       = Some(d.to_string()),
+            Rule::IP(ip)              => rules.addr            = Some((ip.to_string(),)),
+            Rule::Prefix(cidr)        => rules.addr            = Some((addr(cidr),)),
+            Rule::Range(range)        => rules.addr            = Some((single(&range)?,)),
+            Rule::Port(port)          => rules.port            = Some((port.to_string(),)),
+            Rule::PortRange(lo, hi)   => rules.port            = Some((format!("{}-{}", lo, hi),)),
+            Rule::Protocol(proto)     => rules.protocol        = Some([proto.into()]),
+            Rule::Asn(asn)            => rules.asn             = Some((asn.to_string(),)),
+            Rule::Vlan(vlan)          => rules.vlans           = Some((vlan.to_string(),)),
+            Rule::LastHopAsName(name) => rules.lasthop_as_name = Some((name,)),
+            Rule::NextHopAsn(asn)     => rules.nexthop_asn     = Some((asn.to_string(),)),
+            Rule::NextHopAsName(name) => rules.nexthop_as_name = Some((name,)),
+            Rule::AsPath(path)        => rules.bgp_aspath      = Some((path,)),
+            Rule::Community(c)        => rules.bgp_community   = Some((c.to_string(),)),
+            Rule::TcpFlags(flags)     => rules.tcp_flags       = Some((flags,)),
+            Rule::Mac(mac)            => rules.mac             = Some((mac.to_string(),)),
+            Rule::Country(country)    => rules.country         = Some((country.to_string(),)),
+            Rule::Site(site)          => rules.site            = Some((site,)),
+            Rule::DeviceType(kind)    => rules.device_type     = Some((kind,)),
+            Rule::DeviceName(name)    => rules.device_name     = Some((name,)),
+            Rule::InterfaceName(name) => rules.interface_name  = Some((name,)),
+            Rule::NextHop(ip)         => rules.next_hop        = Some((ip.to_string(),)),
         };
-        rules
+        Ok(rules)
+    }
+}
+
+fn single(range: &net::Range) -> Result<String, Error> {
+    match range.cidrs()[..] {
+        [cidr] => Ok(addr(cidr)),
+        _      => Err(Error::Invalid(format!("range {} spans several prefixes", range))),
+    }
+}
+
+impl TryFrom<(&str, &super::Request)> for Batch {
+    type Error = Error;
+
+    fn try_from((column, r): (&str, &super::Request)) -> Result<Self, Self::Error> {
+        let mut batch = batch(column);
+        batch.replace_all(r.replace_all).complete(r.complete).ttl_minutes(r.ttl_minutes);
+
+        if !r.upserts.is_empty() {
+            batch.change(Upsert::try_from((column, r.upserts.as_slice()))?);
+        }
+
+        if !r.deletes.is_empty() {
+            let mut delete = delete(column);
+            r.deletes.iter().for_each(|d| { delete.value(&d.value); });
+            batch.change(delete);
+        }
+
+        Ok(batch)
+    }
+}
+
+impl TryFrom<(&str, &[super::Upsert])> for Upsert {
+    type Error = Error;
+
+    fn try_from((column, upserts): (&str, &[super::Upsert])) -> Result<Self, Self::Error> {
+        let mut map = HashMap::<String, Vec<Rules>>::new();
+        for upsert in upserts {
+            let Values(values) = Values::try_from(upsert)?;
+            for (value, sets) in values {
+                map.entry(value).or_default().extend(sets);
+            }
+        }
+        Ok(Upsert(column.to_owned(), Values(map)))
+    }
+}
+
+impl TryFrom<&super::Upsert> for Values {
+    type Error = Error;
+
+    fn try_from(upsert: &super::Upsert) -> Result<Self, Self::Error> {
+        match upsert {
+            super::Upsert::Small(small) => Values::try_from(small),
+            super::Upsert::Large(large) => Values::try_from(large),
+        }
+    }
+}
+
+impl TryFrom<&super::Small> for Values {
+    type Error = Error;
+
+    fn try_from(small: &super::Small) -> Result<Self, Self::Error> {
+        let rules = Rules::try_from(&small.criteria.0)?;
+        Ok(Values::from((small.value.as_str(), rules)))
+    }
+}
+
+impl TryFrom<&super::Large> for Values {
+    type Error = Error;
+
+    fn try_from(large: &super::Large) -> Result<Self, Self::Error> {
+        let sets = large.criteria.iter().map(Rules::try_from).collect::<Result<Vec<_>, _>>()?;
+        let mut map = HashMap::new();
+        map.insert(large.value.clone(), sets);
+        Ok(Values(map))
+    }
+}
+
+impl TryFrom<&super::Rule> for Rules {
+    type Error = Error;
+
+    fn try_from(rule: &super::Rule) -> Result<Self, Self::Error> {
+        Rules::try_from(&super::Rules::from(rule.clone()))
+    }
+}
+
+impl TryFrom<&super::Rules> for Rules {
+    type Error = Error;
+
+    fn try_from(r: &super::Rules) -> Result<Self, Self::Error> {
+        let mut rules = Vec::new();
+
+        if let Some(direction) = &r.direction {
+            rules.push(Rule::Direction(direction.parse()?));
+        }
+
+        for port in &r.port {
+            rules.push(match port.split_once('-') {
+                Some((lo, hi)) => Rule::port_range(parse("port", lo)?, parse("port", hi)?)?,
+                None           => Rule::Port(parse("port", port)?),
+            });
+        }
+
+        for addr in &r.addr {
+            rules.push(match addr.split_once('-') {
+                Some((start, end)) => Rule::range(parse("addr", start)?, parse("addr", end)?)?,
+                None               => match parse::<Cidr>("addr", addr)? {
+                    cidr if cidr.is_host() => Rule::IP(cidr.addr()),
+                    cidr                   => Rule::Prefix(cidr),
+                },
+            });
+        }
+
+        if let Some(flags) = r.tcp_flags {
+            rules.push(Rule::TcpFlags(flags));
+        }
+
+        for protocol in &r.protocol {
+            rules.push(Rule::Protocol(parse("protocol", &protocol.to_string())?));
+        }
+
+        each(&mut rules, &r.asn,             |v| Ok(Rule::Asn(parse("asn", v)?)))?;
+        each(&mut rules, &r.vlans,           |v| Ok(Rule::Vlan(parse("vlans", v)?)))?;
+        each(&mut rules, &r.lasthop_as_name, |v| Ok(Rule::LastHopAsName(v.to_owned())))?;
+        each(&mut rules, &r.nexthop_asn,     |v| Ok(Rule::NextHopAsn(parse("nexthop_asn", v)?)))?;
+        each(&mut rules, &r.nexthop_as_name, |v| Ok(Rule::NextHopAsName(v.to_owned())))?;
+        each(&mut rules, &r.bgp_aspath,      |v| Ok(Rule::AsPath(v.to_owned())))?;
+        each(&mut rules, &r.bgp_community,   |v| Ok(Rule::Community(parse("bgp_community", v)?)))?;
+        each(&mut rules, &r.mac,             |v| Ok(Rule::Mac(parse("mac", v)?)))?;
+        each(&mut rules, &r.country,         |v| Ok(Rule::Country(parse("country", v)?)))?;
+        each(&mut rules, &r.site,            |v| Ok(Rule::Site(v.to_owned())))?;
+        each(&mut rules, &r.device_type,     |v| Ok(Rule::DeviceType(v.to_owned())))?;
+        each(&mut rules, &r.interface_name,  |v| Ok(Rule::InterfaceName(v.to_owned())))?;
+        each(&mut rules, &r.device_name,     |v| Ok(Rule::DeviceName(v.to_owned())))?;
+        each(&mut rules, &r.next_hop,        |v| Ok(Rule::NextHop(parse("next_hop", v)?)))?;
+
+        Ok(match rules.len() {
+            0 => Empty,
+            1 => One(rules.remove(0)),
+            _ => All(rules),
+        })
+    }
+}
+
+fn each<F: Fn(&str) -> Result<Rule, Error>>(rules: &mut Vec<Rule>, vec: &[String], f: F) -> Result<(), Error> {
+    for v in vec {
+        rules.push(f(v)?);
+    }
+    Ok(())
+}
+
+fn parse<T: FromStr>(field: &str, s: &str) -> Result<T, Error> {
+    s.trim().parse().map_err(|_| Error::Invalid(format!("invalid {} {}", field, s.trim())))
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Direction::Src    => "src",
+            Direction::Dst    => "dst",
+            Direction::Either => "either",
+        })
+    }
+}
+
+impl FromStr for Direction {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "src"    => Ok(Direction::Src),
+            "dst"    => Ok(Direction::Dst),
+            "either" => Ok(Direction::Either),
+            _        => Err(Error::Invalid(format!("invalid direction {}", s))),
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
+    use crate::tag;
     use super::{*, Rule::*};
 
     #[test]
@@ -175,4 +598,284 @@ mod test {
             Port(22),
         ]))).into());
     }
+
+    #[test]
+    fn delete_values() {
+        let mut change = delete("c_foo");
+        change.value("bar").value("baz").value("bar");
+
+        let (column, deletes): (String, Vec<tag::Delete>) = change.into();
+        let values = deletes.into_iter().map(|d| d.value).collect::<Vec<_>>();
+
+        assert_eq!("c_foo", column);
+        assert_eq!(vec!["bar", "baz"], values);
+    }
+
+    #[test]
+    fn request_round_trip() {
+        let mut change = upsert("c_foo");
+        change.value("a").when(Port(22));
+        change.value("b").when(Prefix("10.0.0.0/24".parse().unwrap())).and(Protocol(6)).and(Rule::Direction(super::Direction::Src));
+        change.value("c").when(Rule::range("10.0.0.1".parse().unwrap(), "10.0.0.5".parse().unwrap()).unwrap());
+        change.value("d").when(Mac("00:11:22:33:44:55".parse().unwrap())).and(Community("65000:1".parse().unwrap()));
+
+        let mut removed = delete("c_foo");
+        removed.value("e");
+
+        let mut original = batch("c_foo");
+        original.change(change).change(removed).ttl_minutes(5);
+        let (column, request) = original.build().unwrap();
+
+        let batch = Batch::try_from((column.as_str(), &request)).unwrap();
+        let (_, rebuilt) = batch.build().unwrap();
+
+        assert_eq!(request, rebuilt);
+    }
+
+    #[test]
+    fn wire_to_rules() {
+        let rules = tag::Rules {
+            direction:     Some("dst".to_owned()),
+            port:          vec!["80-88".to_owned()],
+            bgp_community: vec!["4200000000:1:2".to_owned(), "^65000:.*$".to_owned()],
+            addr:          vec!["10.0.0.1".to_owned()],
+            ..Default::default()
+        };
+
+        assert_eq!(All(vec![
+            Rule::Direction(super::Direction::Dst),
+            PortRange(80, 88),
+            IP("10.0.0.1".parse().unwrap()),
+            Community("4200000000:1:2".parse().unwrap()),
+            Community("^65000:.*$".parse().unwrap()),
+        ]), Rules::try_from(&rules).unwrap());
+
+        let rule = tag::Rule { asn: Some(("64512".to_owned(),)), ..Default::default() };
+        assert_eq!(One(Asn(64512)), Rules::try_from(&rule).unwrap());
+        assert_eq!(Empty, Rules::try_from(&tag::Rules::default()).unwrap());
+    }
+
+    #[test]
+    fn wire_to_rules_invalid() {
+        let invalid = |rules: tag::Rules| Rules::try_from(&rules).unwrap_err();
+
+        assert_eq!(Error::Invalid("invalid port 70000".to_owned()), invalid(tag::Rules {
+            port: vec!["70000".to_owned()],
+            ..Default::default()
+        }));
+
+        assert_eq!(Error::Invalid("invalid protocol 300".to_owned()), invalid(tag::Rules {
+            protocol: vec![300],
+            ..Default::default()
+        }));
+
+        assert_eq!(Error::Invalid("invalid mac 00:11".to_owned()), invalid(tag::Rules {
+            mac: vec!["00:11".to_owned()],
+            ..Default::default()
+        }));
+
+        assert_eq!(Error::Invalid("invalid port range 443-80".to_owned()), invalid(tag::Rules {
+            port: vec!["443-80".to_owned()],
+            ..Default::default()
+        }));
+
+        assert_eq!(Error::Invalid("invalid bgp_community 65000".to_owned()), invalid(tag::Rules {
+            bgp_community: vec!["65000".to_owned()],
+            ..Default::default()
+        }));
+    }
+
+    #[test]
+    fn wire_to_values() {
+        let port = |port: &str| tag::Rules { port: vec![port.to_owned()], ..Default::default() };
+
+        let large = tag::Upsert::Large(tag::Large {
+            value:    "a".to_owned(),
+            criteria: vec![port("22"), port("23")],
+        });
+
+        let mut expect = upsert("c_foo");
+        expect.value("a").when(Port(22));
+        expect.or_value("a").when(Port(23));
+        assert_eq!(expect, Upsert::try_from(("c_foo", &[large][..])).unwrap());
+
+        let small = |port: &str| tag::Upsert::Small(tag::Small {
+            value:    "a".to_owned(),
+            criteria: (tag::Rule { port: Some((port.to_owned(),)), ..Default::default() },),
+        });
+        assert_eq!(expect, Upsert::try_from(("c_foo", &[small("22"), small("23")][..])).unwrap());
+    }
+
+    #[test]
+    fn upsert_alternative_criteria() {
+        let mut change = upsert("c_foo");
+        change.value("bar").when(Port(22)).and(Protocol(6));
+        change.or_value("bar").when(Rule::prefix("10.0.0.0/24").unwrap());
+        change.or_value("bar");
+
+        let (_, upserts): (String, Vec<tag::Upsert>) = change.into();
+        let json = serde_json::to_value(&upserts).unwrap();
+
+        assert_eq!(serde_json::json!([{
+            "value":    "bar",
+            "criteria": [
+                {"port": ["22"], "protocol": [6]},
+                {"addr": ["10.0.0.0/24"]},
+            ],
+        }]), json);
+    }
+
+    #[test]
+    fn batch_merge() {
+        let mut first = upsert("c_foo");
+        first.value("a").when(Port(22));
+        first.value("b").when(Port(23));
+        first.value("c");
+
+        let mut removed = delete("c_foo");
+        removed.value("a").value("d");
+
+        let mut second = upsert("c_foo");
+        second.value("d").when(Port(25));
+
+        let mut batch = batch("c_foo");
+        batch.change(first).change(removed).change(second).replace_all(true).ttl_minutes(5);
+
+        let (column, request) = batch.build().unwrap();
+        let json = serde_json::to_value(&request).unwrap();
+
+        assert_eq!("c_foo", column);
+        assert_eq!(serde_json::json!({
+            "replace_all": true,
+            "complete":    true,
+            "ttl_minutes": 5,
+            "upserts":     [
+                {"value": "b", "criteria": [{"port": ["23"]}]},
+                {"value": "d", "criteria": [{"port": ["25"]}]},
+            ],
+            "deletes":     [{"value": "a"}],
+        }), json);
+    }
+
+    #[test]
+    fn batch_column_mismatch() {
+        let mut batch = batch("c_foo");
+        batch.change(delete("c_bar"));
+        assert!(batch.build().is_err());
+    }
+
+    #[test]
+    fn upsert_aggregate_prefixes() {
+        let mut change = upsert("c_foo");
+        change.value("bar")
+            .when(Rule::prefix("10.0.1.0/24").unwrap())
+            .and(Rule::prefix("10.0.0.0/24").unwrap())
+            .and(IP("10.0.0.7".parse().unwrap()))
+            .and(IP("10.0.2.1".parse().unwrap()))
+            .and(Rule::range("10.0.3.0".parse().unwrap(), "10.0.3.255".parse().unwrap()).unwrap())
+            .and(Port(443));
+
+        let (_, upserts): (String, Vec<tag::Upsert>) = change.into();
+        let json = serde_json::to_value(&upserts).unwrap();
+
+        assert_eq!(serde_json::json!([{
+            "value":    "bar",
+            "criteria": [{
+                "port": ["443"],
+                "addr": ["10.0.0.0/23", "10.0.2.1", "10.0.3.0/24"],
+            }],
+        }]), json);
+    }
+
+    #[test]
+    fn upsert_range() {
+        let start = "10.0.0.1".parse().unwrap();
+        let end   = "10.0.0.4".parse().unwrap();
+
+        let mut change = upsert("c_foo");
+        change.value("bar").when(Rule::range(start, end).unwrap());
+
+        let (_, upserts): (String, Vec<tag::Upsert>) = change.into();
+        let json = serde_json::to_value(&upserts).unwrap();
+
+        assert_eq!(serde_json::json!([{
+            "value":    "bar",
+            "criteria": [{"addr": ["10.0.0.1", "10.0.0.2/31", "10.0.0.4"]}],
+        }]), json);
+
+        assert!(Rule::range(end, start).is_err());
+        assert!(Rule::prefix("10.0.0.1/24").is_err());
+    }
+
+    #[test]
+    fn range_round_trip() {
+        let range = |start: &str, end: &str| Rule::range(start.parse().unwrap(), end.parse().unwrap()).unwrap();
+
+        let mut change = upsert("c_foo");
+        change.value("small").when(range("10.0.0.0", "10.0.0.255"));
+        change.value("large").when(range("10.0.1.1", "10.0.1.4"));
+
+        let (column, mut upserts): (String, Vec<tag::Upsert>) = change.into();
+        upserts.sort_by(|a, b| a.value().cmp(b.value()));
+
+        assert_eq!(serde_json::json!([{
+            "value":    "large",
+            "criteria": [{"addr": ["10.0.1.1", "10.0.1.2/31", "10.0.1.4"]}],
+        }, {
+            "value":    "small",
+            "criteria": [{"addr": ["10.0.0.0/24"]}],
+        }]), serde_json::to_value(&upserts).unwrap());
+
+        let (_, mut rebuilt): (String, Vec<tag::Upsert>) = Upsert::try_from((column.as_str(), upserts.as_slice())).unwrap().into();
+        rebuilt.sort_by(|a, b| a.value().cmp(b.value()));
+        assert_eq!(upserts, rebuilt);
+
+        assert!(tag::Rule::try_from(range("10.0.1.1", "10.0.1.4")).is_err());
+    }
+
+    #[test]
+    fn upsert_small_criteria() {
+        let mut change = upsert("c_foo");
+        change.value("bar").when(Community("65000:100".parse().unwrap()));
+
+        let (_, upserts): (String, Vec<tag::Upsert>) = change.into();
+        let json = serde_json::to_value(&upserts).unwrap();
+
+        assert_eq!(serde_json::json!([{
+            "value":    "bar",
+            "criteria": [{"bgp_community": ["65000:100"]}],
+        }]), json);
+    }
+
+    #[test]
+    fn upsert_large_criteria() {
+        let mut change = upsert("c_foo");
+        change.value("bar")
+            .when(Direction(super::Direction::Dst))
+            .and(PortRange(8000, 8080))
+            .and(Protocol(6))
+            .and(Asn(64512))
+            .and(Mac("00:1a:2b:3c:4d:5e".parse().unwrap()))
+            .and(Country("us".parse().unwrap()))
+            .and(TcpFlags(0x02))
+            .and(TcpFlags(0x10))
+            .and(DeviceName("edge-1".to_owned()));
+
+        let (_, upserts): (String, Vec<tag::Upsert>) = change.into();
+        let json = serde_json::to_value(&upserts).unwrap();
+
+        assert_eq!(serde_json::json!([{
+            "value":    "bar",
+            "criteria": [{
+                "direction":   "dst",
+                "port":        ["8000-8080"],
+                "protocol":    [6],
+                "asn":         ["64512"],
+                "mac":         ["00:1a:2b:3c:4d:5e"],
+                "country":     ["US"],
+                "tcp_flags":   0x12,
+                "device_name": ["edge-1"],
+            }],
+        }]), json);
+    }
 }
@@ -1,48 +1,472 @@
+use std::collections::{HashMap, HashSet};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::thread::{self, JoinHandle};
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use backoff::ExponentialBackoff;
+use backoff::backoff::Backoff;
 use crossbeam_channel::*;
-use log::{debug, error};
-use super::{Request, Response};
+use log::{debug, error, warn};
+use super::{Request, Response, Upsert};
+use super::dry_run::DryRun;
+use super::journal::{Entry, Journal};
 use crate::{Client as ApiClient, Error};
+use crate::core::Dimension;
 use Error::*;
 
 pub struct Client {
-    sender: Sender<(String, Request)>,
-    thread: JoinHandle<Result<(), Error>>,
+    client:  Arc<ApiClient>,
+    senders: Vec<Sender<Submission>>,
+    threads: Vec<JoinHandle<Result<(), Error>>>,
+    stats:   Arc<Counters>,
+    journal: Option<Journal>,
+    sending: Mutex<()>,
+    columns: Mutex<HashMap<String, Dimension>>,
+}
+
+pub struct Receipt {
+    result: Receiver<Result<String, Error>>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Config {
+    pub window:  Option<Duration>,
+    pub workers: usize,
+    pub journal: Option<PathBuf>,
+    pub retries: usize,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Default, Debug)]
+pub struct Stats {
+    pub success: u64,
+    pub failure: u64,
+}
+
+struct Submission {
+    column:  String,
+    request: Request,
+    result:  Sender<Result<String, Error>>,
+    entry:   Option<Entry>,
+}
+
+struct Pending {
+    column:   String,
+    request:  Request,
+    results:  Vec<Sender<Result<String, Error>>>,
+    entries:  Vec<Entry>,
+    deadline: Instant,
+}
+
+struct Stalled {
+    head:     Pending,
+    error:    Error,
+    queue:    Vec<Pending>,
+    backoff:  ExponentialBackoff,
+    retries:  usize,
+    deadline: Instant,
+}
+
+#[derive(Default)]
+struct Counters {
+    success: AtomicU64,
+    failure: AtomicU64,
 }
 
 impl Client {
     pub fn new(c: ApiClient) -> Self {
-        let (tx, rx) = bounded(1024);
-        let task     = || poll(rx, c);
+        Self::start(c, Config::default(), None)
+    }
+
+    pub fn with_config(c: ApiClient, cfg: Config) -> Result<Self, Error> {
+        let (journal, items) = match &cfg.journal {
+            Some(dir) => {
+                let (journal, items) = Journal::open(dir)?;
+                (Some(journal), items)
+            },
+            None => (None, Vec::new()),
+        };
+
+        let client = Self::start(c, cfg, journal);
+
+        for (entry, column, request) in items {
+            debug!("replaying journal entry {:?}", entry);
+            let (tx, _) = bounded(1);
+            client.worker(&column).send(Submission {
+                column,
+                request,
+                result:  tx,
+                entry:   Some(entry),
+            }).map_err(|_| stopped())?;
+        }
+
+        Ok(client)
+    }
+
+    fn start(c: ApiClient, cfg: Config, journal: Option<Journal>) -> Self {
+        let client  = Arc::new(c);
+        let stats   = Arc::new(Counters::default());
+        let window  = cfg.window.unwrap_or_default();
+        let workers = cfg.workers.max(1);
+        let retries = cfg.retries;
+
+        let mut senders = Vec::with_capacity(workers);
+        let mut threads = Vec::with_capacity(workers);
+
+        for _ in 0..workers {
+            let (tx, rx) = bounded(1024);
+            let client   = client.clone();
+            let stats    = stats.clone();
+            let task     = move || poll(rx, client, stats, window, retries);
+            senders.push(tx);
+            threads.push(thread::spawn(task));
+        }
+
         Self {
-            sender: tx,
-            thread: thread::spawn(task),
+            client,
+            senders,
+            threads,
+            stats,
+            journal,
+            sending: Mutex::new(()),
+            columns: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn ensure(&self, column: &str, display_name: &str, kind: &str) -> Result<Dimension, Error> {
+        let lock   = || self.columns.lock().map_err(|_| Other("poisoned lock".to_owned()));
+        let cached = lock()?.get(column).cloned();
+
+        // the lock isn't held across API calls, so concurrent callers
+        // for a new column may both try to add it
+        let dimension = match cached {
+            Some(dimension) => dimension,
+            None            => {
+                let dimensions = self.client.get_custom_dimensions()?.dimensions;
+                let dimension  = match dimensions.into_iter().find(|d| d.name == column) {
+                    Some(dimension) => dimension,
+                    None            => self.client.add_custom_dimension(&Dimension {
+                        name:         column.to_owned(),
+                        display_name: display_name.to_owned(),
+                        kind:         kind.to_owned(),
+                        ..Default::default()
+                    })?,
+                };
+                lock()?.insert(column.to_owned(), dimension.clone());
+                dimension
+            },
+        };
+
+        if dimension.kind != kind {
+            let msg = format!("dimension {} has type {}, not {}", column, dimension.kind, kind);
+            return Err(Invalid(msg));
         }
+
+        Ok(dimension)
     }
 
-    pub fn send(&self, c: &str, r: Request, d: Duration) -> Result<(), Error> {
-        Ok(self.sender.send_timeout((c.to_owned(), r), d)?)
+    pub fn send(&self, c: &str, r: Request, d: Duration) -> Result<Receipt, Error> {
+        // journal order must match the order requests reach the worker
+        let _sending = self.sending.lock().map_err(|_| Other("poisoned lock".to_owned()))?;
+
+        let entry = match &self.journal {
+            Some(journal) => Some(journal.push(c, &r)?),
+            None          => None,
+        };
+
+        let (tx, rx) = bounded(1);
+        let submission = Submission {
+            column:  c.to_owned(),
+            request: r,
+            result:  tx,
+            entry,
+        };
+
+        if let Err(e) = self.worker(c).send_timeout(submission, d) {
+            if let Some(entry) = e.into_inner().entry {
+                entry.ack()?;
+            }
+            return Err(Timeout);
+        }
+
+        Ok(Receipt { result: rx })
+    }
+
+    pub fn dry_run(&self, c: &str, r: &Request) -> Result<DryRun, Error> {
+        self.client.dry_run_populators(c, r)
+    }
+
+    fn worker(&self, column: &str) -> &Sender<Submission> {
+        let mut hasher = DefaultHasher::new();
+        column.hash(&mut hasher);
+        &self.senders[hasher.finish() as usize % self.senders.len()]
+    }
+
+    pub fn stats(&self) -> Stats {
+        Stats {
+            success: self.stats.success.load(Ordering::Relaxed),
+            failure: self.stats.failure.load(Ordering::Relaxed),
+        }
     }
 
     pub fn stop(self) -> Result<(), Error> {
-        drop(self.sender);
-        self.thread.join()?
+        drop(self.senders);
+        self.threads.into_iter().map(|thread| thread.join()?).fold(Ok(()), Result::and)
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            window:  None,
+            workers: 1,
+            journal: None,
+            retries: 10,
+        }
     }
 }
 
-fn poll(rx: Receiver<(String, Request)>, c: ApiClient) -> Result<(), Error> {
-    while let Ok((column, request)) = rx.recv() {
-        match c.update_populators(&column, &request) {
-            Ok(Response{guid, ..}) => debug!("submitted: {}", guid),
-            Err(App(e, _))         => error!("tag API error {}", e),
-            Err(e)                 => error!("request error {}", e),
+impl Receipt {
+    pub fn wait(self) -> Result<String, Error> {
+        self.result.recv().unwrap_or_else(|_| Err(stopped()))
+    }
+
+    pub fn wait_timeout(&self, d: Duration) -> Result<String, Error> {
+        match self.result.recv_timeout(d) {
+            Ok(result)                          => result,
+            Err(RecvTimeoutError::Timeout)      => Err(Timeout),
+            Err(RecvTimeoutError::Disconnected) => Err(stopped()),
+        }
+    }
+
+    pub fn try_wait(&self) -> Option<Result<String, Error>> {
+        match self.result.try_recv() {
+            Ok(result)                      => Some(result),
+            Err(TryRecvError::Empty)        => None,
+            Err(TryRecvError::Disconnected) => Some(Err(stopped())),
+        }
+    }
+}
+
+fn poll(rx: Receiver<Submission>, c: Arc<ApiClient>, stats: Arc<Counters>, window: Duration, retries: usize) -> Result<(), Error> {
+    let mut pending = Vec::<Pending>::new();
+    let mut stalled = Vec::<Stalled>::new();
+    let mut failed  = HashMap::<String, Error>::new();
+
+    loop {
+        let retry = stalled.iter().map(|s| s.deadline).min();
+        let next  = match pending.first().map(|p| p.deadline).into_iter().chain(retry).min() {
+            Some(deadline) => rx.recv_deadline(deadline),
+            None           => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+
+        match next {
+            Ok(s)                               => queue(&mut pending, &mut stalled, &failed, s, window, &c, &stats),
+            Err(RecvTimeoutError::Timeout)      => (),
+            Err(RecvTimeoutError::Disconnected) => break,
         }
+
+        let now = Instant::now();
+        while pending.first().is_some_and(|p| p.deadline <= now) {
+            submit(&c, &stats, &mut stalled, &failed, pending.remove(0));
+        }
+
+        let (due, waiting): (Vec<_>, Vec<_>) = stalled.drain(..).partition(|s| s.deadline <= now);
+        stalled = waiting;
+        for s in due {
+            match resume(&c, &stats, s) {
+                Some(s) if s.retries >= retries => fail(&stats, &mut failed, s),
+                Some(s)                         => stalled.push(s),
+                None                            => (),
+            }
+        }
+    }
+
+    for p in pending {
+        submit(&c, &stats, &mut stalled, &failed, p);
+    }
+
+    // stalled requests stay in the journal and replay in order on the next start
+    for Stalled{head, error, queue, ..} in stalled {
+        let count   = queue.len() + 1;
+        let journal = queue.iter().chain(Some(&head)).filter(|p| !p.entries.is_empty()).count();
+        if journal > 0 {
+            warn!("{} requests for {} left in journal", journal, head.column);
+        }
+        if count > journal {
+            warn!("{} requests for {} dropped", count - journal, head.column);
+        }
+        finish(&stats, head, Err(error));
+        for p in queue {
+            finish(&stats, p, Err(stopped()));
+        }
+    }
+
+    Ok(())
+}
+
+fn queue(pending: &mut Vec<Pending>, stalled: &mut Vec<Stalled>, failed: &HashMap<String, Error>, s: Submission, window: Duration, c: &ApiClient, stats: &Counters) {
+    let Submission{column, request, result, entry} = s;
+
+    let request = match pending.iter().position(|p| p.column == column) {
+        Some(index) => match merge(&mut pending[index].request, request) {
+            Ok(()) => {
+                pending[index].results.push(result);
+                pending[index].entries.extend(entry);
+                return;
+            },
+            Err(request) => {
+                submit(c, stats, stalled, failed, pending.remove(index));
+                request
+            },
+        },
+        None => request,
+    };
+
+    pending.push(Pending {
+        column,
+        request,
+        results:  vec![result],
+        entries:  entry.into_iter().collect(),
+        deadline: Instant::now() + window,
+    });
+}
+
+fn merge(into: &mut Request, r: Request) -> Result<(), Request> {
+    let compatible = !into.replace_all && !r.replace_all
+        && into.complete && r.complete
+        && into.ttl_minutes == r.ttl_minutes
+        && into.guid.is_none() && r.guid.is_none();
+
+    if !compatible {
+        return Err(r);
     }
 
+    let upserts = r.upserts.iter().map(Upsert::value);
+    let deletes = r.deletes.iter().map(|d| d.value.as_str());
+    let values  = upserts.chain(deletes).map(str::to_owned).collect::<HashSet<_>>();
+
+    into.upserts.retain(|u| !values.contains(u.value()));
+    into.deletes.retain(|d| !values.contains(&d.value));
+    into.upserts.extend(r.upserts);
+    into.deletes.extend(r.deletes);
+
     Ok(())
 }
 
+// requests for a stalled column wait behind the one being retried,
+// and fail without being sent once the column has given up
+fn submit(c: &ApiClient, stats: &Counters, stalled: &mut Vec<Stalled>, failed: &HashMap<String, Error>, p: Pending) {
+    if let Some(e) = failed.get(&p.column) {
+        finish(stats, p, Err(e.clone()));
+        return;
+    }
+
+    match stalled.iter_mut().find(|s| s.head.column == p.column) {
+        Some(s) => s.queue.push(p),
+        None    => stalled.extend(send(c, stats, p).map(|(p, e)| stall(p, e, Vec::new()))),
+    }
+}
+
+fn resume(c: &ApiClient, stats: &Counters, s: Stalled) -> Option<Stalled> {
+    let Stalled{head, queue, mut backoff, retries, ..} = s;
+
+    debug!("retrying request for {}", head.column);
+
+    if let Some((head, error)) = send(c, stats, head) {
+        let deadline = Instant::now() + backoff.next_backoff().unwrap_or(backoff.max_interval);
+        return Some(Stalled { head, error, queue, backoff, retries: retries + 1, deadline });
+    }
+
+    let mut queue = queue.into_iter();
+    while let Some(p) = queue.next() {
+        if let Some((p, e)) = send(c, stats, p) {
+            return Some(stall(p, e, queue.collect()));
+        }
+    }
+
+    None
+}
+
+fn stall(head: Pending, error: Error, queue: Vec<Pending>) -> Stalled {
+    let mut backoff = ExponentialBackoff {
+        max_elapsed_time: None,
+        ..Default::default()
+    };
+    let deadline = Instant::now() + backoff.next_backoff().unwrap_or(backoff.max_interval);
+    Stalled { head, error, queue, backoff, retries: 0, deadline }
+}
+
+// a column that runs out of retries keeps its requests in the journal,
+// and later ones fail too so they replay in order on the next start
+fn fail(stats: &Counters, failed: &mut HashMap<String, Error>, s: Stalled) {
+    let Stalled{head, error, queue, retries, ..} = s;
+
+    warn!("giving up on {} after {} retries", head.column, retries);
+
+    failed.insert(head.column.clone(), error.clone());
+    finish(stats, head, Err(error.clone()));
+    for p in queue {
+        finish(stats, p, Err(error.clone()));
+    }
+}
+
+// a journaled request that fails with a retryable error is handed back to be retried
+fn send(c: &ApiClient, stats: &Counters, p: Pending) -> Option<(Pending, Error)> {
+    let outcome = match c.update_populators(&p.column, &p.request) {
+        Ok(Response{guid, ..}) => {
+            debug!("submitted: {}", guid);
+            Ok(guid)
+        },
+        Err(e) => {
+            match &e {
+                App(e, _) => error!("tag API error {}", e),
+                e         => error!("request error {}", e),
+            }
+            Err(e)
+        },
+    };
+
+    match outcome {
+        Err(e) if !p.entries.is_empty() && !permanent(&e) => Some((p, e)),
+        outcome                                          => {
+            finish(stats, p, outcome);
+            None
+        },
+    }
+}
+
+fn finish(stats: &Counters, p: Pending, outcome: Result<String, Error>) {
+    let Pending{results, entries, ..} = p;
+    let count = results.len() as u64;
+
+    let (counter, done) = match &outcome {
+        Ok(_)  => (&stats.success, true),
+        Err(e) => (&stats.failure, permanent(e)),
+    };
+    counter.fetch_add(count, Ordering::Relaxed);
+
+    for entry in entries.into_iter().filter(|_| done) {
+        if let Err(e) = entry.ack() {
+            error!("journal error {}", e);
+        }
+    }
+
+    for result in results {
+        let _ = result.send(outcome.clone());
+    }
+}
+
+fn permanent(e: &Error) -> bool {
+    matches!(e.clone().into_backoff(), backoff::Error::Permanent(_))
+}
+
+fn stopped() -> Error {
+    Other("tag client stopped".to_owned())
+}
+
 impl<T> From<SendTimeoutError<T>> for Error {
     fn from(_: SendTimeoutError<T>) -> Self {
         Error::Timeout
@@ -0,0 +1,150 @@
+use std::collections::BTreeSet;
+use std::fmt::{self, Display};
+use crate::{Client, Error};
+use crate::core::Populator;
+use super::{Request, BATCH_LIMIT};
+use super::reconcile::{compare, criteria, Diff};
+
+// stands in for the guid of later chunks in dry run bodies
+pub const GUID_PLACEHOLDER: &str = "<guid of previous chunk>";
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct DryRun {
+    pub bodies: Vec<String>,
+    pub diff:   Option<Diff>,
+}
+
+impl Client {
+    pub fn dry_run_populators(&self, column: &str, r: &Request) -> Result<DryRun, Error> {
+        let diff = match self.get_populators(column) {
+            Ok(current)                                  => Some(diff(r, &current)?),
+            Err(Error::App(_, 404) | Error::Status(404)) => None,
+            Err(e)                                       => return Err(e),
+        };
+
+        Ok(DryRun {
+            bodies: bodies(r)?,
+            diff,
+        })
+    }
+}
+
+pub fn bodies(r: &Request) -> Result<Vec<String>, Error> {
+    if r.len() <= BATCH_LIMIT {
+        return Ok(vec![serde_json::to_string(r)?]);
+    }
+
+    // later chunks carry the guid returned for the previous one, which
+    // is only known once the batch is actually sent, so their bodies
+    // hold a placeholder instead
+    r.chunks(BATCH_LIMIT).into_iter().enumerate().map(|(n, mut chunk)| {
+        if n > 0 {
+            chunk.guid = Some(GUID_PLACEHOLDER.to_owned());
+        }
+        Ok(serde_json::to_string(&chunk)?)
+    }).collect()
+}
+
+pub fn diff(r: &Request, current: &[Populator]) -> Result<Diff, Error> {
+    let mut diff = compare(&criteria(&r.upserts), current)?;
+
+    let deleted = r.deletes.iter().map(|d| d.value.as_str()).collect::<BTreeSet<_>>();
+    diff.removed.retain(|value| r.replace_all || deleted.contains(value.as_str()));
+
+    Ok(diff)
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "no changes");
+        }
+
+        let lines = self.added.iter().map(|v| ('+', v))
+            .chain(self.changed.iter().map(|v| ('~', v)))
+            .chain(self.removed.iter().map(|v| ('-', v)));
+
+        for (mark, value) in lines {
+            writeln!(f, "{} {}", mark, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::tag::{Delete, Upsert};
+    use crate::tag::change::{upsert, Rule::*};
+    use super::*;
+
+    #[test]
+    fn diff_request() {
+        let current = vec![
+            populator("same",    "10.0.0.1"),
+            populator("changed", "10.0.0.2"),
+            populator("deleted", "10.0.0.3"),
+            populator("kept",    "10.0.0.4"),
+        ];
+
+        let mut change = upsert("c_foo");
+        change.value("same").when(IP("10.0.0.1".parse().unwrap()));
+        change.value("changed").when(IP("10.0.0.9".parse().unwrap()));
+        change.value("added").when(IP("10.0.0.5".parse().unwrap()));
+        let (_, upserts): (String, Vec<Upsert>) = change.into();
+
+        let mut request = Request {
+            replace_all: false,
+            complete:    true,
+            ttl_minutes: 0,
+            upserts,
+            deletes:     vec![Delete{value: "deleted".to_owned()}, Delete{value: "missing".to_owned()}],
+            guid:        None,
+        };
+
+        let diff = diff(&request, &current).unwrap();
+        assert_eq!(vec!["added"],   diff.added);
+        assert_eq!(vec!["changed"], diff.changed);
+        assert_eq!(vec!["deleted"], diff.removed);
+        assert_eq!("+ added\n~ changed\n- deleted\n", diff.to_string());
+
+        request.replace_all = true;
+        request.deletes.clear();
+        assert_eq!(vec!["deleted", "kept"], super::diff(&request, &current).unwrap().removed);
+
+        assert_eq!("no changes\n", Diff::default().to_string());
+    }
+
+    #[test]
+    fn chunk_bodies() {
+        let request = Request {
+            replace_all: false,
+            complete:    true,
+            ttl_minutes: 0,
+            upserts:     Vec::new(),
+            deletes:     (0..=BATCH_LIMIT).map(|n| Delete{value: n.to_string()}).collect(),
+            guid:        None,
+        };
+
+        let bodies = bodies(&request).unwrap();
+        let guids  = bodies.iter().map(|body| {
+            serde_json::from_str::<Request>(body).unwrap().guid
+        }).collect::<Vec<_>>();
+
+        assert_eq!(vec![None, Some(GUID_PLACEHOLDER.to_owned())], guids);
+    }
+
+    fn populator(value: &str, addr: &str) -> Populator {
+        Populator {
+            value: value.to_owned(),
+            addr:  addr.to_owned(),
+            ..Default::default()
+        }
+    }
+}
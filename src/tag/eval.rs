@@ -0,0 +1,249 @@
+use std::net::IpAddr;
+use std::str::FromStr;
+use crate::net::{Cidr, Community, CommunityMatch, Country, MacAddr};
+use super::{Rules, Upsert};
+
+// criteria on fields a flow does not carry, like AS path,
+// and BGP community regexes never match
+#[derive(Clone, Eq, PartialEq, Default, Debug)]
+pub struct Flow {
+    pub src:         Endpoint,
+    pub dst:         Endpoint,
+    pub protocol:    Option<u8>,
+    pub tcp_flags:   Option<u16>,
+    pub device_name: Option<String>,
+    pub device_type: Option<String>,
+    pub site:        Option<String>,
+}
+
+#[derive(Clone, Eq, PartialEq, Default, Debug)]
+pub struct Endpoint {
+    pub addr:        Option<IpAddr>,
+    pub port:        Option<u16>,
+    pub asn:         Option<u32>,
+    pub vlan:        Option<u16>,
+    pub interface:   Option<String>,
+    pub mac:         Option<MacAddr>,
+    pub country:     Option<Country>,
+    pub communities: Vec<Community>,
+}
+
+#[derive(Clone, Eq, PartialEq, Default, Debug)]
+pub struct Tags {
+    pub src: Vec<String>,
+    pub dst: Vec<String>,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum Side {
+    Src,
+    Dst,
+}
+
+pub fn evaluate(upserts: &[Upsert], flow: &Flow) -> Tags {
+    let mut tags = Tags::default();
+
+    for upsert in upserts {
+        let criteria = upsert.criteria();
+        let value    = upsert.value();
+
+        if criteria.iter().any(|r| matches(r, flow, Side::Src)) {
+            tags.src.push(value.to_owned());
+        }
+
+        if criteria.iter().any(|r| matches(r, flow, Side::Dst)) {
+            tags.dst.push(value.to_owned());
+        }
+    }
+
+    for vec in [&mut tags.src, &mut tags.dst] {
+        vec.sort();
+        vec.dedup();
+    }
+
+    tags
+}
+
+// Option::is_none_or needs a newer toolchain than the rest of the crate
+#[allow(clippy::unnecessary_map_or)]
+fn matches(r: &Rules, flow: &Flow, side: Side) -> bool {
+    let direction = r.direction.as_deref().map(str::to_lowercase);
+    let allowed   = matches!(
+        (direction.as_deref(), side),
+        (None | Some("either"), _) | (Some("src"), Side::Src) | (Some("dst"), Side::Dst)
+    );
+
+    if !allowed || r == &Rules::default() {
+        return false;
+    }
+
+    let end = match side {
+        Side::Src => &flow.src,
+        Side::Dst => &flow.dst,
+    };
+
+    let unsupported = [
+        &r.lasthop_as_name,
+        &r.nexthop_asn,
+        &r.nexthop_as_name,
+        &r.bgp_aspath,
+        &r.next_hop,
+    ];
+
+    unsupported.iter().all(|v| v.is_empty())
+        && any(&r.addr, end.addr, addr)
+        && any(&r.port, end.port, |s, port| range(s, port.into()))
+        && any(&r.asn, end.asn, |s, asn| range(s, asn.into()))
+        && any(&r.vlans, end.vlan, |s, vlan| range(s, vlan.into()))
+        && any(&r.interface_name, end.interface.as_deref(), name)
+        && any(&r.mac, end.mac, |s, mac| s.parse() == Ok(mac))
+        && any(&r.country, end.country, |s, country| s.parse() == Ok(country))
+        && any(&r.device_name, flow.device_name.as_deref(), name)
+        && any(&r.device_type, flow.device_type.as_deref(), name)
+        && any(&r.site, flow.site.as_deref(), name)
+        && (r.bgp_community.is_empty() || r.bgp_community.iter().any(|s| {
+            match CommunityMatch::from_str(s.trim()) {
+                Ok(CommunityMatch::Literal(c)) => end.communities.contains(&c),
+                _                              => false,
+            }
+        }))
+        && (r.protocol.is_empty() || flow.protocol.is_some_and(|p| r.protocol.contains(&p.into())))
+        && r.tcp_flags.map_or(true, |mask| flow.tcp_flags.is_some_and(|flags| flags & mask == mask))
+}
+
+// an empty list matches anything, otherwise one entry must match the field
+fn any<T: Copy, F: Fn(&str, T) -> bool>(list: &[String], field: Option<T>, f: F) -> bool {
+    match field {
+        _ if list.is_empty() => true,
+        Some(field)          => list.iter().any(|s| f(s.trim(), field)),
+        None                 => false,
+    }
+}
+
+fn addr(s: &str, ip: IpAddr) -> bool {
+    match s.split_once('-') {
+        Some((start, end)) => match (start.trim().parse::<IpAddr>(), end.trim().parse::<IpAddr>()) {
+            (Ok(start), Ok(end)) => start <= ip && ip <= end && start.is_ipv4() == ip.is_ipv4(),
+            _                    => false,
+        },
+        None => s.parse::<Cidr>().is_ok_and(|cidr| cidr.contains(&ip)),
+    }
+}
+
+fn range(s: &str, n: u64) -> bool {
+    let parse = |s: &str| s.trim().parse::<u64>().ok();
+    match s.split_once('-') {
+        Some((lo, hi)) => matches!((parse(lo), parse(hi)), (Some(lo), Some(hi)) if lo <= n && n <= hi),
+        None           => parse(s) == Some(n),
+    }
+}
+
+fn name(s: &str, name: &str) -> bool {
+    s.eq_ignore_ascii_case(name)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::tag::change::{upsert, Direction, Rule::*};
+    use super::*;
+
+    #[test]
+    fn evaluate_direction() {
+        let mut change = upsert("c_test");
+        change.value("client").when(Direction(Direction::Src)).and(IP("10.0.0.1".parse().unwrap()));
+        change.value("server").when(Direction(Direction::Dst)).and(IP("10.0.0.1".parse().unwrap()));
+        change.value("either").when(IP("10.0.0.1".parse().unwrap()));
+        change.value("other").when(IP("10.0.0.2".parse().unwrap()));
+
+        let upserts = upserts(change);
+
+        let tags = evaluate(&upserts, &flow("10.0.0.1", 40000, "192.168.0.1", 443));
+        assert_eq!(vec!["client", "either"], tags.src);
+        assert!(tags.dst.is_empty());
+
+        let tags = evaluate(&upserts, &flow("192.168.0.1", 40000, "10.0.0.1", 443));
+
+        assert!(tags.src.is_empty());
+        assert_eq!(vec!["either", "server"], tags.dst);
+    }
+
+    #[test]
+    fn evaluate_and_or() {
+        let mut change = upsert("c_test");
+        change.value("https").when(Prefix("10.0.0.0/24".parse().unwrap())).and(Port(443)).and(Protocol(6));
+        change.value("ssh").when(Port(22));
+        change.value("range").when(PortRange(40000, 40010));
+        let mut upserts = upserts(change);
+
+        upserts.push(Upsert::Large(crate::tag::Large {
+            value:    "web".to_owned(),
+            criteria: vec![
+                Rules { port: vec!["80".to_owned()], ..Default::default() },
+                Rules { port: vec!["443".to_owned()], ..Default::default() },
+            ],
+        }));
+
+        let mut flow = flow("192.168.0.1", 40005, "10.0.0.9", 443);
+        flow.protocol = Some(6);
+
+        let tags = evaluate(&upserts, &flow);
+        assert_eq!(vec!["range"], tags.src);
+        assert_eq!(vec!["https", "web"], tags.dst);
+
+        flow.protocol = Some(17);
+        let tags = evaluate(&upserts, &flow);
+        assert_eq!(vec!["web"], tags.dst);
+    }
+
+    #[test]
+    fn evaluate_fields() {
+        let mut change = upsert("c_test");
+        change.value("asn").when(Asn(64512));
+        change.value("vlan").when(Vlan(100));
+        change.value("iface").when(InterfaceName("eth0".to_owned()));
+        change.value("mac").when(Mac("00:11:22:33:44:55".parse().unwrap()));
+        change.value("country").when(Country("US".parse().unwrap()));
+        change.value("community").when(Community("65000:1".parse().unwrap()));
+        change.value("large").when(Community("4200000000:1:2".parse().unwrap()));
+        change.value("regex").when(Community("^65000:.*$".parse().unwrap()));
+        change.value("device").when(DeviceName("edge1".to_owned())).and(Site("ams".to_owned()));
+        change.value("syn").when(TcpFlags(0x02));
+        change.value("aspath").when(AsPath("^64512".to_owned()));
+
+        let mut flow = flow("10.0.0.1", 40000, "10.0.0.2", 443);
+        flow.src.asn         = Some(64512);
+        flow.src.vlan        = Some(100);
+        flow.src.interface   = Some("ETH0".to_owned());
+        flow.src.mac         = "00-11-22-33-44-55".parse().ok();
+        flow.src.country     = "us".parse().ok();
+        flow.src.communities = vec!["65000:1".parse().unwrap(), "4200000000:1:2".parse().unwrap()];
+        flow.device_name     = Some("edge1".to_owned());
+        flow.site            = Some("AMS".to_owned());
+        flow.tcp_flags       = Some(0x12);
+
+        let tags = evaluate(&upserts(change), &flow);
+        assert_eq!(vec!["asn", "community", "country", "device", "iface", "large", "mac", "syn", "vlan"], tags.src);
+        assert_eq!(vec!["device", "syn"], tags.dst);
+    }
+
+    fn upserts(change: crate::tag::change::Upsert) -> Vec<Upsert> {
+        let (_, upserts): (String, Vec<Upsert>) = change.into();
+        upserts
+    }
+
+    fn flow(src: &str, sport: u16, dst: &str, dport: u16) -> Flow {
+        Flow {
+            src: Endpoint {
+                addr: src.parse().ok(),
+                port: Some(sport),
+                ..Default::default()
+            },
+            dst: Endpoint {
+                addr: dst.parse().ok(),
+                port: Some(dport),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,465 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::convert::TryFrom;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use crate::{Client, Error};
+use crate::core::Populator;
+use super::{change, Large, Request, Rules, Upsert};
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum Format {
+    Csv,
+    JsonLines,
+    Yaml,
+}
+
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize, Default, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct Row {
+    pub value:           String,
+    pub direction:       String,
+    pub port:            String,
+    pub protocol:        String,
+    pub asn:             String,
+    pub vlans:           String,
+    pub lasthop_as_name: String,
+    pub nexthop_asn:     String,
+    pub nexthop_as_name: String,
+    pub bgp_aspath:      String,
+    pub bgp_community:   String,
+    pub tcp_flags:       String,
+    pub addr:            String,
+    pub mac:             String,
+    pub country:         String,
+    pub site:            String,
+    pub device_type:     String,
+    pub interface_name:  String,
+    pub device_name:     String,
+    pub next_hop:        String,
+}
+
+// YAML entries are numbered by position in the document, not by line
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct LineError {
+    pub line:  usize,
+    pub error: Error,
+}
+
+impl Client {
+    pub fn export_populators<W: Write>(&self, column: &str, format: Format, w: W) -> Result<(), Error> {
+        export(format, &self.get_populators(column)?, w)
+    }
+}
+
+pub fn import<R: Read>(column: &str, format: Format, r: R) -> Result<Request, Vec<LineError>> {
+    let rows = match format {
+        Format::Csv       => csv_rows(r),
+        Format::JsonLines => json_rows(r),
+        Format::Yaml      => yaml_rows(r),
+    }?;
+
+    let mut values = BTreeMap::<String, Vec<Upsert>>::new();
+    let mut errors = Vec::new();
+
+    for (line, row) in rows {
+        match upserts(column, row) {
+            Ok(vec) => vec.into_iter().for_each(|u| values.entry(u.value().to_owned()).or_default().push(u)),
+            Err(e)  => errors.push(LineError { line, error: e }),
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let upserts = values.into_iter().flat_map(|(value, mut vec)| match vec.len() {
+        1 => vec.pop(),
+        _ => Some(Upsert::Large(Large {
+            criteria: vec.iter().flat_map(Upsert::criteria).collect(),
+            value,
+        })),
+    }).collect();
+
+    Ok(Request {
+        replace_all: false,
+        complete:    true,
+        ttl_minutes: 0,
+        upserts,
+        deletes:     Vec::new(),
+        guid:        None,
+    })
+}
+
+pub fn export<W: Write>(format: Format, populators: &[Populator], mut w: W) -> Result<(), Error> {
+    let mut rows = populators.iter().map(Row::from).collect::<Vec<_>>();
+    rows.sort();
+
+    match format {
+        Format::Csv => {
+            let mut w = csv::Writer::from_writer(w);
+            rows.iter().try_for_each(|row| w.serialize(row))?;
+            w.flush()?;
+        },
+        Format::JsonLines => {
+            for row in rows {
+                serde_json::to_writer(&mut w, &compact(row)?)?;
+                w.write_all(b"\n")?;
+            }
+        },
+        Format::Yaml => {
+            let rows = rows.into_iter().map(compact).collect::<Result<Vec<_>, _>>()?;
+            serde_norway::to_writer(w, &rows)?;
+        },
+    }
+
+    Ok(())
+}
+
+fn upserts(column: &str, row: Row) -> Result<Vec<Upsert>, Error> {
+    let value = row.value.trim().to_owned();
+    if value.is_empty() {
+        return Err(Error::Invalid("missing value".to_owned()));
+    }
+
+    let rules = change::Rules::try_from(&Rules::try_from(&Populator::from(row))?)?;
+    if rules.is_empty() {
+        return Err(Error::Invalid(format!("no criteria for {}", value)));
+    }
+
+    let (_, upserts): (String, Vec<Upsert>) = change::Upsert::from((column, (value.as_str(), rules))).into();
+    Ok(upserts)
+}
+
+fn csv_rows<R: Read>(r: R) -> Result<Vec<(usize, Row)>, Vec<LineError>> {
+    let mut reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(r);
+    let headers    = reader.headers().map_err(|e| vec![failure(1, e)])?.clone();
+
+    let mut rows   = Vec::new();
+    let mut errors = Vec::new();
+
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(e)     => {
+                errors.push(failure(position(&e), e));
+                continue;
+            },
+        };
+
+        let line = record.position().map_or(0, |p| p.line() as usize);
+        match record.deserialize::<Row>(Some(&headers)) {
+            Ok(row) => rows.push((line, row)),
+            Err(e)  => errors.push(failure(line, e)),
+        }
+    }
+
+    finish(rows, errors)
+}
+
+fn json_rows<R: Read>(r: R) -> Result<Vec<(usize, Row)>, Vec<LineError>> {
+    let mut rows   = Vec::new();
+    let mut errors = Vec::new();
+
+    for (index, line) in BufReader::new(r).lines().enumerate() {
+        let line = match line {
+            Ok(line) if line.trim().is_empty() => continue,
+            Ok(line)                           => line,
+            Err(e)                             => return Err(vec![failure(index + 1, e)]),
+        };
+
+        match serde_json::from_str(&line).map_err(Error::from).and_then(row) {
+            Ok(row) => rows.push((index + 1, row)),
+            Err(e)  => errors.push(LineError { line: index + 1, error: e }),
+        }
+    }
+
+    finish(rows, errors)
+}
+
+fn yaml_rows<R: Read>(r: R) -> Result<Vec<(usize, Row)>, Vec<LineError>> {
+    let values = serde_norway::from_reader::<_, Vec<serde_norway::Value>>(r).map_err(|e| {
+        vec![failure(e.location().map_or(0, |l| l.line()), e)]
+    })?;
+
+    let mut rows   = Vec::new();
+    let mut errors = Vec::new();
+
+    for (index, value) in values.into_iter().enumerate() {
+        match serde_json::to_value(value).map_err(Error::from).and_then(row) {
+            Ok(row) => rows.push((index + 1, row)),
+            Err(e)  => errors.push(LineError { line: index + 1, error: e }),
+        }
+    }
+
+    finish(rows, errors)
+}
+
+fn row(value: Value) -> Result<Row, Error> {
+    let text = |value: Value| match value {
+        Value::Null      => String::new(),
+        Value::String(s) => s,
+        value            => value.to_string(),
+    };
+
+    let map = match value {
+        Value::Object(map) => map.into_iter().map(|(k, v)| {
+            let v = match v {
+                Value::Array(vec) => vec.into_iter().map(text).collect::<Vec<_>>().join(","),
+                v                 => text(v),
+            };
+            (k, Value::String(v))
+        }).collect(),
+        _ => return Err(Error::Invalid("expected an object".to_owned())),
+    };
+
+    Ok(serde_json::from_value(Value::Object(map))?)
+}
+
+fn compact(row: Row) -> Result<Map<String, Value>, Error> {
+    match serde_json::to_value(row)? {
+        Value::Object(mut map) => {
+            map.retain(|_, v| v.as_str() != Some(""));
+            Ok(map)
+        },
+        _ => Err(Error::Other("row is not an object".to_owned())),
+    }
+}
+
+fn finish(rows: Vec<(usize, Row)>, errors: Vec<LineError>) -> Result<Vec<(usize, Row)>, Vec<LineError>> {
+    match errors.is_empty() {
+        true  => Ok(rows),
+        false => Err(errors),
+    }
+}
+
+fn failure<E: Display>(line: usize, e: E) -> LineError {
+    LineError {
+        line,
+        error: Error::Invalid(e.to_string()),
+    }
+}
+
+fn position(e: &csv::Error) -> usize {
+    e.position().map_or(0, |p| p.line() as usize)
+}
+
+impl From<&Populator> for Row {
+    fn from(p: &Populator) -> Self {
+        Row {
+            value:           p.value.clone(),
+            direction:       p.direction.clone(),
+            port:            p.port.clone(),
+            protocol:        p.protocol.clone(),
+            asn:             p.asn.clone(),
+            vlans:           p.vlans.clone(),
+            lasthop_as_name: p.lasthop_as_name.clone(),
+            nexthop_asn:     p.nexthop_asn.clone(),
+            nexthop_as_name: p.nexthop_as_name.clone(),
+            bgp_aspath:      p.bgp_aspath.clone(),
+            bgp_community:   p.bgp_community.clone(),
+            tcp_flags:       p.tcp_flags.clone(),
+            addr:            p.addr.clone(),
+            mac:             p.mac.clone(),
+            country:         p.country.clone(),
+            site:            p.site.clone(),
+            device_type:     p.device_type.clone(),
+            interface_name:  p.interface_name.clone(),
+            device_name:     p.device_name.clone(),
+            next_hop:        p.next_hop.clone(),
+        }
+    }
+}
+
+impl From<Row> for Populator {
+    fn from(r: Row) -> Self {
+        Populator {
+            id:              0,
+            value:           r.value,
+            direction:       r.direction,
+            port:            r.port,
+            protocol:        r.protocol,
+            asn:             r.asn,
+            vlans:           r.vlans,
+            lasthop_as_name: r.lasthop_as_name,
+            nexthop_asn:     r.nexthop_asn,
+            nexthop_as_name: r.nexthop_as_name,
+            bgp_aspath:      r.bgp_aspath,
+            bgp_community:   r.bgp_community,
+            tcp_flags:       r.tcp_flags,
+            addr:            r.addr,
+            mac:             r.mac,
+            country:         r.country,
+            site:            r.site,
+            device_type:     r.device_type,
+            interface_name:  r.interface_name,
+            device_name:     r.device_name,
+            next_hop:        r.next_hop,
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "csv"            => Ok(Format::Csv),
+            "jsonl" | "json" => Ok(Format::JsonLines),
+            "yaml"  | "yml"  => Ok(Format::Yaml),
+            _                => Err(Error::Invalid(format!("unknown format {}", s))),
+        }
+    }
+}
+
+impl Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.error {
+            Error::Invalid(msg) | Error::Other(msg) => write!(f, "line {}: {}", self.line, msg),
+            error                                   => write!(f, "line {}: {:?}", self.line, error),
+        }
+    }
+}
+
+impl From<csv::Error> for Error {
+    fn from(err: csv::Error) -> Self {
+        Error::Other(err.to_string())
+    }
+}
+
+impl From<serde_norway::Error> for Error {
+    fn from(err: serde_norway::Error) -> Self {
+        Error::Other(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::tag::{Rules, Small};
+    use super::*;
+
+    #[test]
+    fn import_csv() {
+        let csv = "value,addr,port\nalice,10.0.0.1,22\nalice,10.0.1.0/24,\nbob,10.0.0.2,\n";
+        let request = import("c_test", Format::Csv, csv.as_bytes()).unwrap();
+
+        assert_eq!(vec![
+            Upsert::Large(Large {
+                value:    "alice".to_owned(),
+                criteria: vec![
+                    Rules {
+                        port: vec!["22".to_owned()],
+                        addr: vec!["10.0.0.1".to_owned()],
+                        ..Default::default()
+                    },
+                    Rules {
+                        addr: vec!["10.0.1.0/24".to_owned()],
+                        ..Default::default()
+                    },
+                ],
+            }),
+            small("bob", "10.0.0.2"),
+        ], request.upserts);
+    }
+
+    #[test]
+    fn import_to_batch() {
+        let csv = "value,addr,port\nalice,10.0.0.1,22\nalice,10.0.1.0/24,\nalice,,80-88\nbob,10.0.0.2,\n";
+        let request = import("c_test", Format::Csv, csv.as_bytes()).unwrap();
+
+        let batch = change::Batch::try_from(("c_test", &request)).unwrap();
+        let (_, rebuilt) = batch.build().unwrap();
+
+        assert_eq!(request, rebuilt);
+    }
+
+    #[test]
+    fn import_errors() {
+        let csv = "value,addr,port\nalice,10.0.0.1,22\n,10.0.0.2,\nbob,10.0.0.300,\neve,,99999\n";
+        let errors = import("c_test", Format::Csv, csv.as_bytes()).unwrap_err();
+        let errors = errors.iter().map(LineError::to_string).collect::<Vec<_>>();
+
+        assert_eq!(vec![
+            "line 3: missing value",
+            "line 4: invalid addr 10.0.0.300",
+            "line 5: invalid port 99999",
+        ], errors);
+
+        let jsonl  = "{\"value\": \"alice\", \"addr\": \"10.0.0.1\"}\n\n{\"value\": \"bob\", \"adr\": \"10.0.0.2\"}\n";
+        let errors = import("c_test", Format::JsonLines, jsonl.as_bytes()).unwrap_err();
+        assert_eq!(vec![3], errors.iter().map(|e| e.line).collect::<Vec<_>>());
+
+        let yaml   = "- value: alice\n  addr: 10.0.0.1\n- value: bob\n";
+        let errors = import("c_test", Format::Yaml, yaml.as_bytes()).unwrap_err();
+        assert_eq!(vec![2], errors.iter().map(|e| e.line).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn import_yaml_lists() {
+        let yaml = "- value: alice\n  addr: [10.0.0.1, 10.0.0.2]\n  port: 22\n";
+        let request = import("c_test", Format::Yaml, yaml.as_bytes()).unwrap();
+
+        assert_eq!(vec![Upsert::Large(Large {
+            value:    "alice".to_owned(),
+            criteria: vec![Rules {
+                port: vec!["22".to_owned()],
+                addr: vec!["10.0.0.1".to_owned(), "10.0.0.2".to_owned()],
+                ..Default::default()
+            }],
+        })], request.upserts);
+    }
+
+    #[test]
+    fn export_round_trip() {
+        let populators = vec![
+            populator("bob",   "10.0.0.2", ""),
+            populator("alice", "10.0.0.1", "22"),
+        ];
+
+        for format in [Format::Csv, Format::JsonLines, Format::Yaml] {
+            let mut buf = Vec::new();
+            export(format, &populators, &mut buf).unwrap();
+            let request = import("c_test", format, buf.as_slice()).unwrap();
+
+            assert_eq!(vec![
+                Upsert::Large(Large {
+                    value:    "alice".to_owned(),
+                    criteria: vec![Rules {
+                        port: vec!["22".to_owned()],
+                        addr: vec!["10.0.0.1".to_owned()],
+                        ..Default::default()
+                    }],
+                }),
+                small("bob", "10.0.0.2"),
+            ], request.upserts);
+        }
+
+        let mut buf = Vec::new();
+        export(Format::JsonLines, &populators, &mut buf).unwrap();
+        assert_eq!(concat!(
+            "{\"addr\":\"10.0.0.1\",\"port\":\"22\",\"value\":\"alice\"}\n",
+            "{\"addr\":\"10.0.0.2\",\"value\":\"bob\"}\n",
+        ), String::from_utf8(buf).unwrap());
+    }
+
+    fn small(value: &str, addr: &str) -> Upsert {
+        Upsert::Small(Small {
+            value:    value.to_owned(),
+            criteria: (crate::tag::Rule {
+                addr: Some((addr.to_owned(),)),
+                ..Default::default()
+            },),
+        })
+    }
+
+    fn populator(value: &str, addr: &str, port: &str) -> Populator {
+        Populator {
+            value: value.to_owned(),
+            addr:  addr.to_owned(),
+            port:  port.to_owned(),
+            ..Default::default()
+        }
+    }
+}
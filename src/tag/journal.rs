@@ -0,0 +1,141 @@
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use log::error;
+use serde::{Deserialize, Serialize};
+use super::Request;
+use crate::Error;
+
+pub struct Journal {
+    dir: PathBuf,
+    seq: AtomicU64,
+}
+
+#[derive(Debug)]
+pub struct Entry(PathBuf);
+
+pub type Pending = (Entry, String, Request);
+
+#[derive(Serialize, Deserialize)]
+struct Item<C, R> {
+    column:  C,
+    request: R,
+}
+
+impl Journal {
+    pub fn open<P: AsRef<Path>>(dir: P) -> Result<(Self, Vec<Pending>), Error> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            match path.extension().and_then(|ext| ext.to_str()) {
+                Some("json") => paths.push(path),
+                Some("tmp")  => fs::remove_file(&path)?,
+                _            => (),
+            }
+        }
+        paths.sort();
+
+        let seq = paths.iter().filter_map(|path| {
+            path.file_stem()?.to_str()?.parse::<u64>().ok()
+        }).max().map_or(0, |seq| seq + 1);
+
+        let mut items = Vec::with_capacity(paths.len());
+        for path in paths {
+            match read(&path) {
+                Ok(Item{column, request}) => items.push((Entry(path), column, request)),
+                Err(e)                    => {
+                    error!("invalid journal entry {}: {}", path.display(), e);
+                    fs::rename(&path, path.with_extension("bad"))?;
+                },
+            }
+        }
+
+        Ok((Self { dir, seq: AtomicU64::new(seq) }, items))
+    }
+
+    pub fn push(&self, column: &str, request: &Request) -> Result<Entry, Error> {
+        let seq  = self.seq.fetch_add(1, Ordering::SeqCst);
+        let path = self.dir.join(format!("{:020}.json", seq));
+        let tmp  = path.with_extension("tmp");
+
+        let mut file = File::create(&tmp)?;
+        serde_json::to_writer(&mut file, &Item{column, request})?;
+        file.sync_all()?;
+        fs::rename(&tmp, &path)?;
+
+        Ok(Entry(path))
+    }
+}
+
+impl Entry {
+    pub fn ack(self) -> Result<(), Error> {
+        Ok(fs::remove_file(self.0)?)
+    }
+}
+
+fn read(path: &Path) -> Result<Item<String, Request>, Error> {
+    let file = File::open(path)?;
+    Ok(serde_json::from_reader(file)?)
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::Other(err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+    use std::fs;
+    use std::process;
+    use std::time::{SystemTime, UNIX_EPOCH};
+    use crate::tag::{Delete, Request};
+    use super::*;
+
+    #[test]
+    fn journal_replay() {
+        let dir = tempdir();
+
+        let (journal, items) = Journal::open(&dir).unwrap();
+        assert!(items.is_empty());
+
+        let first  = journal.push("c_a", &request("alice")).unwrap();
+        let second = journal.push("c_b", &request("bob")).unwrap();
+        first.ack().unwrap();
+        drop(second);
+
+        let (journal, items) = Journal::open(&dir).unwrap();
+        let items = items.into_iter().map(|(_, c, r)| (c, r)).collect::<Vec<_>>();
+        assert_eq!(vec![("c_b".to_owned(), request("bob"))], items);
+
+        journal.push("c_c", &request("eve")).unwrap();
+        fs::write(dir.join("00000000000000000000.json"), "{").unwrap();
+
+        let (_, items) = Journal::open(&dir).unwrap();
+        let columns = items.iter().map(|(_, c, _)| c.as_str()).collect::<Vec<_>>();
+        assert_eq!(vec!["c_b", "c_c"], columns);
+        assert!(dir.join("00000000000000000000.bad").exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    fn request(value: &str) -> Request {
+        Request {
+            replace_all: false,
+            complete:    true,
+            ttl_minutes: 0,
+            upserts:     vec![],
+            deletes:     vec![Delete{value: value.to_owned()}],
+            guid:        None,
+        }
+    }
+
+    fn tempdir() -> PathBuf {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+        env::temp_dir().join(format!("kentik-journal-{}-{}", process::id(), nanos))
+    }
+}
@@ -1,42 +1,58 @@
+use std::convert::TryFrom;
 use serde::{Deserialize, Serialize};
+use crate::Error;
+use crate::core::Populator;
 
+pub mod analyze;
 pub mod client;
 pub mod change;
+pub mod dry_run;
+pub mod eval;
+pub mod file;
+pub mod journal;
+pub mod reconcile;
+pub mod refresh;
+pub mod validate;
 
-pub use client::Client;
+pub use client::{Client, Config, Receipt, Stats};
 
-#[derive(Serialize, Deserialize, Debug)]
+pub const BATCH_LIMIT: usize = 5_000;
+
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
 pub struct Request {
     pub replace_all: bool,
     pub complete:    bool,
     pub ttl_minutes: u32,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub upserts:     Vec<Upsert>,
-    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub deletes:     Vec<Delete>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guid:        Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(untagged)]
+#[allow(clippy::large_enum_variant)]
 pub enum Upsert {
     Small(Small),
     Large(Large),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
 pub struct Small {
     pub value:    String,
     pub criteria: (Rule,)
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
 pub struct Large {
     pub value:    String,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub criteria: Vec<Rules>,
 }
 
-#[derive(Serialize, Deserialize, Default, Debug)]
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize, Default, Debug)]
 pub struct Rules {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub direction:       Option<String>,
@@ -77,7 +93,7 @@ pub struct Rules {
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub next_hop:        Vec<String>,
 }
-#[derive(Serialize, Deserialize, Default, Debug)]
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Default, Debug)]
 pub struct Rule {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub direction:       Option<String>,
@@ -119,13 +135,191 @@ pub struct Rule {
     pub next_hop:        Option<(String,)>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
 pub struct Delete {
     pub value: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
 pub struct Response {
-    message: String,
-    guid:    String,
+    pub message: String,
+    pub guid:    String,
+}
+
+impl Request {
+    pub fn len(&self) -> usize {
+        self.upserts.len() + self.deletes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn chunks(&self, limit: usize) -> Vec<Request> {
+        let limit   = limit.max(1);
+        let count   = self.len().div_ceil(limit).max(1);
+        let mut vec = Vec::with_capacity(count);
+
+        let mut upserts = self.upserts.chunks(limit);
+        let mut deletes = self.deletes.iter();
+
+        for n in 0..count {
+            let upserts = upserts.next().map(<[_]>::to_vec).unwrap_or_default();
+            let deletes = deletes.by_ref().take(limit - upserts.len()).cloned().collect();
+
+            vec.push(Request {
+                replace_all: self.replace_all,
+                complete:    self.complete && n == count - 1,
+                ttl_minutes: self.ttl_minutes,
+                upserts,
+                deletes,
+                guid:        self.guid.clone(),
+            });
+        }
+
+        vec
+    }
+}
+
+impl From<Rule> for Rules {
+    fn from(r: Rule) -> Self {
+        Rules {
+            direction:       r.direction,
+            port:            r.port.map(|(v,)| vec![v]).unwrap_or_default(),
+            protocol:        r.protocol.map(|v| v.to_vec()).unwrap_or_default(),
+            asn:             r.asn.map(|(v,)| vec![v]).unwrap_or_default(),
+            vlans:           r.vlans.map(|(v,)| vec![v]).unwrap_or_default(),
+            lasthop_as_name: r.lasthop_as_name.map(|(v,)| vec![v]).unwrap_or_default(),
+            nexthop_asn:     r.nexthop_asn.map(|(v,)| vec![v]).unwrap_or_default(),
+            nexthop_as_name: r.nexthop_as_name.map(|(v,)| vec![v]).unwrap_or_default(),
+            bgp_aspath:      r.bgp_aspath.map(|(v,)| vec![v]).unwrap_or_default(),
+            bgp_community:   r.bgp_community.map(|(v,)| vec![v]).unwrap_or_default(),
+            tcp_flags:       r.tcp_flags.map(|(v,)| v),
+            addr:            r.addr.map(|(v,)| vec![v]).unwrap_or_default(),
+            mac:             r.mac.map(|(v,)| vec![v]).unwrap_or_default(),
+            country:         r.country.map(|(v,)| vec![v]).unwrap_or_default(),
+            site:            r.site.map(|(v,)| vec![v]).unwrap_or_default(),
+            device_type:     r.device_type.map(|(v,)| vec![v]).unwrap_or_default(),
+            interface_name:  r.interface_name.map(|(v,)| vec![v]).unwrap_or_default(),
+            device_name:     r.device_name.map(|(v,)| vec![v]).unwrap_or_default(),
+            next_hop:        r.next_hop.map(|(v,)| vec![v]).unwrap_or_default(),
+        }
+    }
+}
+
+impl Upsert {
+    pub fn value(&self) -> &str {
+        match self {
+            Upsert::Small(small) => &small.value,
+            Upsert::Large(large) => &large.value,
+        }
+    }
+
+    pub fn criteria(&self) -> Vec<Rules> {
+        match self {
+            Upsert::Small(small) => vec![small.criteria.0.clone().into()],
+            Upsert::Large(large) => large.criteria.clone(),
+        }
+    }
+}
+
+impl TryFrom<&Populator> for Rules {
+    type Error = Error;
+
+    fn try_from(p: &Populator) -> Result<Self, Self::Error> {
+        let invalid = |field: &str, value: &str| {
+            Error::Invalid(format!("invalid {} {} for {}", field, value, p.value))
+        };
+
+        let protocol = split(&p.protocol).into_iter().map(|v| {
+            v.parse().map_err(|_| invalid("protocol", &v))
+        }).collect::<Result<_, _>>()?;
+
+        let tcp_flags = match p.tcp_flags.trim() {
+            ""    => None,
+            flags => Some(flags.parse().map_err(|_| invalid("tcp_flags", flags))?),
+        };
+
+        let direction = match p.direction.trim() {
+            ""        => None,
+            direction => Some(direction.to_owned()),
+        };
+
+        Ok(Rules {
+            direction,
+            port:            split(&p.port),
+            protocol,
+            asn:             split(&p.asn),
+            vlans:           split(&p.vlans),
+            lasthop_as_name: split(&p.lasthop_as_name),
+            nexthop_asn:     split(&p.nexthop_asn),
+            nexthop_as_name: split(&p.nexthop_as_name),
+            bgp_aspath:      split(&p.bgp_aspath),
+            bgp_community:   split(&p.bgp_community),
+            tcp_flags,
+            addr:            split(&p.addr),
+            mac:             split(&p.mac),
+            country:         split(&p.country),
+            site:            split(&p.site),
+            device_type:     split(&p.device_type),
+            interface_name:  split(&p.interface_name),
+            device_name:     split(&p.device_name),
+            next_hop:        split(&p.next_hop),
+        })
+    }
+}
+
+fn split(s: &str) -> Vec<String> {
+    s.split(',').map(str::trim).filter(|v| !v.is_empty()).map(str::to_owned).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn chunk_request() {
+        let request = request(5, 4);
+        let chunks  = request.chunks(3);
+        let sizes   = chunks.iter().map(|r| (r.upserts.len(), r.deletes.len())).collect::<Vec<_>>();
+        let done    = chunks.iter().map(|r| r.complete).collect::<Vec<_>>();
+
+        assert_eq!(vec![(3, 0), (2, 1), (0, 3)], sizes);
+        assert_eq!(vec![false, false, true], done);
+        assert!(chunks.iter().all(|r| r.replace_all && r.ttl_minutes == 5));
+
+        let upserts = chunks.iter().flat_map(|r| r.upserts.iter().map(Upsert::value)).collect::<Vec<_>>();
+        let deletes = chunks.iter().flat_map(|r| r.deletes.iter().map(|d| d.value.as_str())).collect::<Vec<_>>();
+        assert_eq!(request.upserts.iter().map(Upsert::value).collect::<Vec<_>>(), upserts);
+        assert_eq!(request.deletes.iter().map(|d| d.value.as_str()).collect::<Vec<_>>(), deletes);
+    }
+
+    #[test]
+    fn chunk_small_request() {
+        assert_eq!(vec![request(2, 1)], request(2, 1).chunks(3));
+        assert_eq!(vec![request(0, 0)], request(0, 0).chunks(3));
+    }
+
+    #[test]
+    fn chunk_incomplete_request() {
+        let mut request = request(4, 0);
+        request.complete = false;
+        assert!(request.chunks(2).iter().all(|r| !r.complete));
+    }
+
+    fn request(upserts: usize, deletes: usize) -> Request {
+        Request {
+            replace_all: true,
+            complete:    true,
+            ttl_minutes: 5,
+            upserts:     (0..upserts).map(|n| Upsert::Large(Large {
+                value:    format!("u{}", n),
+                criteria: vec![],
+            })).collect(),
+            deletes:     (0..deletes).map(|n| Delete {
+                value: format!("d{}", n),
+            }).collect(),
+            guid:        None,
+        }
+    }
 }
@@ -0,0 +1,211 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryFrom;
+use crate::{Client, Error};
+use crate::core::Populator;
+use crate::net::Cidr;
+use super::{Delete, Large, Request, Response, Rules, Upsert};
+
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct Diff {
+    pub added:   Vec<String>,
+    pub changed: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl Client {
+    pub fn reconcile_populators(&self, column: &str, desired: Vec<Upsert>) -> Result<Option<Response>, Error> {
+        let current = self.get_populators(column)?;
+        let (request, _) = plan(desired, &current)?;
+
+        if request.upserts.is_empty() && request.deletes.is_empty() {
+            return Ok(None);
+        }
+
+        self.update_populators(column, &request).map(Some)
+    }
+}
+
+pub fn plan(desired: Vec<Upsert>, current: &[Populator]) -> Result<(Request, Diff), Error> {
+    let diff = compare(&criteria(&desired), current)?;
+
+    let modified = diff.added.iter().chain(&diff.changed).map(String::as_str).collect::<BTreeSet<_>>();
+    let mut want = BTreeMap::<String, Vec<Upsert>>::new();
+
+    for upsert in desired.into_iter().filter(|u| modified.contains(u.value())) {
+        want.entry(upsert.value().to_owned()).or_default().push(upsert);
+    }
+
+    let mut upserts = Vec::new();
+    for (value, mut vec) in want {
+        match vec.len() {
+            1 => upserts.append(&mut vec),
+            _ => upserts.push(Upsert::Large(Large {
+                criteria: vec.iter().flat_map(Upsert::criteria).collect(),
+                value,
+            })),
+        }
+    }
+
+    let deletes = diff.removed.iter().map(|value| Delete{value: value.clone()}).collect();
+
+    Ok((Request {
+        replace_all: false,
+        complete:    true,
+        ttl_minutes: 0,
+        upserts,
+        deletes,
+        guid:        None,
+    }, diff))
+}
+
+// the normalized criteria sets of each value
+pub(crate) fn criteria(upserts: &[Upsert]) -> BTreeMap<&str, BTreeSet<Rules>> {
+    let mut map = BTreeMap::<&str, BTreeSet<Rules>>::new();
+    for upsert in upserts {
+        map.entry(upsert.value()).or_default().extend(upsert.criteria().into_iter().map(normalize));
+    }
+    map
+}
+
+// compare wanted criteria with the current populators, listing
+// every current value that isn't wanted as removed
+pub(crate) fn compare(want: &BTreeMap<&str, BTreeSet<Rules>>, current: &[Populator]) -> Result<Diff, Error> {
+    let mut have = BTreeMap::<&str, BTreeSet<Rules>>::new();
+
+    for p in current {
+        let rules = normalize(Rules::try_from(p)?);
+        have.entry(&p.value).or_default().insert(rules);
+    }
+
+    let mut diff = Diff::default();
+
+    for (value, rules) in want {
+        match have.remove(value) {
+            Some(existing) if &existing == rules => (),
+            Some(_)                              => diff.changed.push(value.to_string()),
+            None                                 => diff.added.push(value.to_string()),
+        }
+    }
+
+    diff.removed = have.keys().map(|value| value.to_string()).collect();
+
+    Ok(diff)
+}
+
+pub(crate) fn normalize(mut rules: Rules) -> Rules {
+    let sort = |vec: &mut Vec<String>| {
+        vec.sort();
+        vec.dedup();
+    };
+
+    rules.direction = rules.direction.map(|d| d.to_lowercase()).filter(|d| d != "either");
+
+    for addr in rules.addr.iter_mut().chain(rules.next_hop.iter_mut()) {
+        if let Ok(cidr) = addr.parse::<Cidr>() {
+            *addr = match cidr.is_host() {
+                true  => cidr.addr().to_string(),
+                false => cidr.to_string(),
+            };
+        }
+    }
+
+    rules.mac.iter_mut().for_each(|mac| *mac = mac.to_lowercase());
+    rules.country.iter_mut().for_each(|country| *country = country.to_uppercase());
+
+    sort(&mut rules.port);
+    sort(&mut rules.asn);
+    sort(&mut rules.vlans);
+    sort(&mut rules.lasthop_as_name);
+    sort(&mut rules.nexthop_asn);
+    sort(&mut rules.nexthop_as_name);
+    sort(&mut rules.bgp_aspath);
+    sort(&mut rules.bgp_community);
+    sort(&mut rules.addr);
+    sort(&mut rules.mac);
+    sort(&mut rules.country);
+    sort(&mut rules.site);
+    sort(&mut rules.device_type);
+    sort(&mut rules.interface_name);
+    sort(&mut rules.device_name);
+    sort(&mut rules.next_hop);
+
+    rules.protocol.sort_unstable();
+    rules.protocol.dedup();
+
+    rules
+}
+
+#[cfg(test)]
+mod test {
+    use crate::tag::change::{upsert, Rule, Rule::*};
+    use super::*;
+
+    #[test]
+    fn plan_changes() {
+        let current = vec![
+            populator("same",    "10.0.0.1/32", "22"),
+            populator("changed", "10.0.0.2",    "22"),
+            populator("removed", "10.0.0.3",    ""),
+            populator("split",   "10.0.0.4",    ""),
+            populator("split",   "10.0.0.5",    ""),
+        ];
+
+        let mut change = upsert("c_foo");
+        change.value("same").when(Port(22)).and(IP("10.0.0.1".parse().unwrap()));
+        change.value("changed").when(Port(23)).and(IP("10.0.0.2".parse().unwrap()));
+        change.value("added").when(Port(80));
+        let (_, mut desired): (String, Vec<Upsert>) = change.into();
+
+        let mut split = upsert("c_foo");
+        split.value("split").when(IP("10.0.0.5".parse().unwrap()));
+        let (_, vec): (String, Vec<Upsert>) = split.into();
+        desired.extend(vec);
+
+        let mut split = upsert("c_foo");
+        split.value("split").when(IP("10.0.0.4".parse().unwrap()));
+        let (_, vec): (String, Vec<Upsert>) = split.into();
+        desired.extend(vec);
+
+        let (request, diff) = plan(desired, &current).unwrap();
+
+        assert_eq!(vec!["added"],   diff.added);
+        assert_eq!(vec!["changed"], diff.changed);
+        assert_eq!(vec!["removed"], diff.removed);
+
+        let values = request.upserts.iter().map(Upsert::value).collect::<Vec<_>>();
+        assert_eq!(vec!["added", "changed"], values);
+        assert_eq!(vec![Delete{value: "removed".to_owned()}], request.deletes);
+        assert!(!request.replace_all);
+    }
+
+    #[test]
+    fn plan_nothing() {
+        let current = vec![populator("same", "10.0.0.0/8", "80,443")];
+
+        let mut change = upsert("c_foo");
+        change.value("same").when(Port(443)).and(Port(80)).and(Rule::prefix("10.0.0.0/8").unwrap());
+        let (_, desired): (String, Vec<Upsert>) = change.into();
+
+        let (request, diff) = plan(desired, &current).unwrap();
+
+        assert!(request.upserts.is_empty());
+        assert!(request.deletes.is_empty());
+        assert_eq!(Diff::default(), diff);
+    }
+
+    #[test]
+    fn plan_invalid_populator() {
+        let mut current = populator("bad", "", "");
+        current.protocol = "tcp".to_owned();
+        assert!(plan(vec![], &[current]).is_err());
+    }
+
+    fn populator(value: &str, addr: &str, port: &str) -> Populator {
+        Populator {
+            value: value.to_owned(),
+            addr:  addr.to_owned(),
+            port:  port.to_owned(),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use crossbeam_channel::*;
+use log::{debug, error};
+use super::{Client, Receipt, Request, Upsert};
+use crate::Error;
+
+pub type Interval = Box<dyn Fn(u32) -> Duration + Send>;
+
+pub struct Refresher {
+    client: Arc<Client>,
+    sender: Sender<Command>,
+    thread: JoinHandle<Result<(), Error>>,
+}
+
+enum Command {
+    Add(String, String, u32, Vec<Upsert>),
+    Remove(String, String),
+    Clear(String),
+    Sync(Sender<()>),
+}
+
+struct Entry {
+    ttl:     u32,
+    upserts: Vec<Upsert>,
+    due:     Instant,
+}
+
+impl Refresher {
+    pub fn new(c: Client) -> Self {
+        Self::with_interval(c, Box::new(|ttl| Duration::from_secs(u64::from(ttl) * 30)))
+    }
+
+    pub fn with_interval(c: Client, interval: Interval) -> Self {
+        let (tx, rx) = unbounded();
+        let client   = Arc::new(c);
+        let shared   = client.clone();
+        let task     = move || poll(rx, shared, interval);
+        Self {
+            client,
+            sender: tx,
+            thread: thread::spawn(task),
+        }
+    }
+
+    pub fn send(&self, c: &str, r: Request, d: Duration) -> Result<Receipt, Error> {
+        let mut removals  = Vec::new();
+        let mut additions = Vec::new();
+
+        if r.replace_all {
+            removals.push(Command::Clear(c.to_owned()));
+        }
+
+        for delete in &r.deletes {
+            removals.push(Command::Remove(c.to_owned(), delete.value.clone()));
+        }
+
+        let mut values = BTreeMap::<&str, Vec<Upsert>>::new();
+        for upsert in &r.upserts {
+            values.entry(upsert.value()).or_default().push(upsert.clone());
+        }
+
+        // a permanent upsert replaces any TTL criteria still being refreshed
+        for (value, upserts) in values {
+            match r.ttl_minutes {
+                0   => removals.push(Command::Remove(c.to_owned(), value.to_owned())),
+                ttl => additions.push(Command::Add(c.to_owned(), value.to_owned(), ttl, upserts)),
+            }
+        }
+
+        // wait until removed values are no longer refreshed, so a
+        // refresh already in flight can't follow the request
+        if !removals.is_empty() {
+            let (tx, rx) = bounded(1);
+            removals.push(Command::Sync(tx));
+            for command in removals {
+                self.sender.send(command).map_err(|_| stopped())?;
+            }
+            rx.recv().map_err(|_| stopped())?;
+        }
+
+        let receipt = self.client.send(c, r, d)?;
+
+        for command in additions {
+            self.sender.send(command).map_err(|_| stopped())?;
+        }
+
+        Ok(receipt)
+    }
+
+    pub fn remove(&self, column: &str, value: &str) -> Result<(), Error> {
+        let command = Command::Remove(column.to_owned(), value.to_owned());
+        self.sender.send(command).map_err(|_| stopped())
+    }
+
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    pub fn stop(self) -> Result<(), Error> {
+        drop(self.sender);
+        self.thread.join()??;
+        match Arc::try_unwrap(self.client) {
+            Ok(client) => client.stop(),
+            Err(_)     => Err(stopped()),
+        }
+    }
+}
+
+fn poll(rx: Receiver<Command>, c: Arc<Client>, interval: Interval) -> Result<(), Error> {
+    let mut entries = BTreeMap::<(String, String), Entry>::new();
+
+    loop {
+        let next = match entries.values().map(|e| e.due).min() {
+            Some(due) => rx.recv_deadline(due),
+            None      => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+
+        match next {
+            Ok(Command::Add(column, value, ttl, upserts)) => {
+                let due = Instant::now() + interval(ttl);
+                entries.insert((column, value), Entry { ttl, upserts, due });
+            },
+            Ok(Command::Remove(column, value)) => {
+                entries.remove(&(column, value));
+            },
+            Ok(Command::Clear(column)) => {
+                entries.retain(|(c, _), _| *c != column);
+            },
+            Ok(Command::Sync(ack)) => {
+                let _ = ack.send(());
+            },
+            Err(RecvTimeoutError::Timeout)      => (),
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        refresh(&c, &mut entries, &interval);
+    }
+
+    Ok(())
+}
+
+fn refresh(c: &Client, entries: &mut BTreeMap<(String, String), Entry>, interval: &Interval) {
+    let now     = Instant::now();
+    let mut due = BTreeMap::<(&str, u32), Vec<Upsert>>::new();
+
+    for ((column, _), entry) in entries.iter_mut().filter(|(_, e)| e.due <= now) {
+        due.entry((column, entry.ttl)).or_default().extend(entry.upserts.iter().cloned());
+        entry.due = now + interval(entry.ttl);
+    }
+
+    for ((column, ttl), upserts) in due {
+        debug!("refreshing {} values in {}", upserts.len(), column);
+
+        let request = Request {
+            replace_all: false,
+            complete:    true,
+            ttl_minutes: ttl,
+            upserts,
+            deletes:     Vec::new(),
+            guid:        None,
+        };
+
+        if let Err(e) = c.send(column, request, Duration::from_secs(60)) {
+            error!("refresh error {}", e);
+        }
+    }
+}
+
+fn stopped() -> Error {
+    Error::Other("tag refresher stopped".to_owned())
+}
@@ -0,0 +1,293 @@
+use std::fmt::{self, Display};
+use std::net::IpAddr;
+use std::str::FromStr;
+use crate::{Client, Error};
+use crate::core::Dimension;
+use crate::net::{Cidr, CommunityMatch, Country, MacAddr};
+use super::{Request, Rules, Upsert};
+
+pub const MAX_COLUMN_LEN: usize = 20;
+pub const MAX_VALUE_LEN:  usize = 128;
+pub const MAX_RULE_LEN:   usize = 255;
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Violation {
+    pub path:    String,
+    pub message: String,
+}
+
+impl Client {
+    pub fn validate_populators(&self, column: &str, r: &Request) -> Result<Vec<Violation>, Error> {
+        let dimensions = self.get_custom_dimensions()?.dimensions;
+        Ok(validate(column, r, &dimensions))
+    }
+}
+
+pub fn validate(column: &str, r: &Request, dimensions: &[Dimension]) -> Vec<Violation> {
+    let mut v = Validator::default();
+
+    if !column.starts_with("c_") {
+        v.fail("column", format!("{} does not start with c_", column));
+    }
+
+    if column.len() > MAX_COLUMN_LEN {
+        v.fail("column", format!("{} is longer than {} characters", column, MAX_COLUMN_LEN));
+    }
+
+    if !column.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_') {
+        v.fail("column", format!("{} contains invalid characters", column));
+    }
+
+    let kind = dimensions.iter().find(|d| d.name == column).map(|d| d.kind.as_str());
+    if kind.is_none() {
+        v.fail("column", format!("unknown dimension {}", column));
+    }
+
+    for (i, upsert) in r.upserts.iter().enumerate() {
+        let path = format!("upserts[{}]", i);
+        v.value(&path, upsert.value(), kind);
+        match upsert {
+            Upsert::Small(small) => v.rules(&format!("{}.criteria[0]", path), &small.criteria.0.clone().into()),
+            Upsert::Large(large) if large.criteria.is_empty() => v.fail(format!("{}.criteria", path), "empty criteria".to_owned()),
+            Upsert::Large(large) => large.criteria.iter().enumerate().for_each(|(j, rules)| {
+                v.rules(&format!("{}.criteria[{}]", path, j), rules)
+            }),
+        }
+    }
+
+    for (i, delete) in r.deletes.iter().enumerate() {
+        v.value(&format!("deletes[{}]", i), &delete.value, kind);
+    }
+
+    v.violations
+}
+
+#[derive(Default)]
+struct Validator {
+    violations: Vec<Violation>,
+}
+
+impl Validator {
+    fn fail<P: Display>(&mut self, path: P, message: String) {
+        self.violations.push(Violation {
+            path:    path.to_string(),
+            message,
+        });
+    }
+
+    fn value(&mut self, path: &str, value: &str, kind: Option<&str>) {
+        let path = format!("{}.value", path);
+
+        if value.is_empty() {
+            self.fail(&path, "empty value".to_owned());
+        } else if value.len() > MAX_VALUE_LEN {
+            self.fail(&path, format!("value is longer than {} characters", MAX_VALUE_LEN));
+        }
+
+        if kind == Some("uint32") && value.parse::<u32>().is_err() {
+            self.fail(&path, format!("{} is not a uint32", value));
+        }
+    }
+
+    fn rules(&mut self, path: &str, r: &Rules) {
+        if r == &Rules::default() {
+            self.fail(path, "empty criteria".to_owned());
+        }
+
+        if let Some(direction) = &r.direction {
+            if !["src", "dst", "either"].contains(&direction.to_lowercase().as_str()) {
+                self.fail(format!("{}.direction", path), format!("invalid direction {}", direction));
+            }
+        }
+
+        for (i, protocol) in r.protocol.iter().enumerate() {
+            if *protocol > 255 {
+                self.fail(format!("{}.protocol[{}]", path, i), format!("invalid protocol {}", protocol));
+            }
+        }
+
+        if let Some(flags) = r.tcp_flags {
+            if flags > 0x1ff {
+                self.fail(format!("{}.tcp_flags", path), format!("invalid tcp_flags {}", flags));
+            }
+        }
+
+        self.each(path, "port",            &r.port,            |s| range::<u16>(s, u16::MAX.into()));
+        self.each(path, "asn",             &r.asn,             |s| s.parse::<u32>().is_ok());
+        self.each(path, "vlans",           &r.vlans,           |s| range::<u16>(s, 4095));
+        self.each(path, "lasthop_as_name", &r.lasthop_as_name, |_| true);
+        self.each(path, "nexthop_asn",     &r.nexthop_asn,     |s| s.parse::<u32>().is_ok());
+        self.each(path, "nexthop_as_name", &r.nexthop_as_name, |_| true);
+        self.each(path, "bgp_aspath",      &r.bgp_aspath,      |_| true);
+        self.each(path, "bgp_community",   &r.bgp_community,   community);
+        self.each(path, "addr",            &r.addr,            address);
+        self.each(path, "mac",             &r.mac,             |s| s.parse::<MacAddr>().is_ok());
+        self.each(path, "country",         &r.country,         |s| s.parse::<Country>().is_ok());
+        self.each(path, "site",            &r.site,            |_| true);
+        self.each(path, "device_type",     &r.device_type,     |_| true);
+        self.each(path, "interface_name",  &r.interface_name,  |_| true);
+        self.each(path, "device_name",     &r.device_name,     |_| true);
+        self.each(path, "next_hop",        &r.next_hop,        |s| s.parse::<Cidr>().is_ok());
+    }
+
+    fn each(&mut self, path: &str, field: &str, values: &[String], valid: fn(&str) -> bool) {
+        for (i, value) in values.iter().enumerate() {
+            let path = format!("{}.{}[{}]", path, field, i);
+            if value.trim().is_empty() {
+                self.fail(path, format!("empty {}", field));
+            } else if value.len() > MAX_RULE_LEN {
+                self.fail(path, format!("{} is longer than {} characters", field, MAX_RULE_LEN));
+            } else if !valid(value.trim()) {
+                self.fail(path, format!("invalid {} {}", field, value));
+            }
+        }
+    }
+}
+
+fn range<T: FromStr + Into<u64>>(s: &str, max: u64) -> bool {
+    let parse = |s: &str| s.trim().parse::<T>().ok().map(Into::into).filter(|n| *n <= max);
+    match s.split_once('-') {
+        Some((lo, hi)) => matches!((parse(lo), parse(hi)), (Some(lo), Some(hi)) if lo <= hi),
+        None           => parse(s).is_some(),
+    }
+}
+
+fn address(s: &str) -> bool {
+    let parse = |s: &str| s.trim().parse::<IpAddr>().ok();
+    match s.split_once('-') {
+        Some((start, end)) => match (parse(start), parse(end)) {
+            (Some(IpAddr::V4(a)), Some(IpAddr::V4(b))) => a <= b,
+            (Some(IpAddr::V6(a)), Some(IpAddr::V6(b))) => a <= b,
+            _                                          => false,
+        },
+        None => s.parse::<Cidr>().is_ok(),
+    }
+}
+
+// a literal standard, 4-byte ASN or large community, or a regex over them
+fn community(s: &str) -> bool {
+    s.parse::<CommunityMatch>().is_ok()
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::tag::{self, Delete, Large, Small};
+    use super::*;
+
+    #[test]
+    fn validate_valid() {
+        let request = request(vec![
+            small("alice", tag::Rule {
+                addr: Some(("10.0.0.0/24".to_owned(),)),
+                ..Default::default()
+            }),
+            large("bob", Rules {
+                direction:     Some("src".to_owned()),
+                port:          vec!["22".to_owned(), "8000-8080".to_owned()],
+                protocol:      vec![6, 17],
+                asn:           vec!["64512".to_owned()],
+                vlans:         vec!["1-4094".to_owned()],
+                bgp_community: vec!["65000:100".to_owned(), "4200000000:1".to_owned(), "65000:1:2".to_owned(), "^65000:.*$".to_owned()],
+                addr:          vec!["10.0.0.1-10.0.0.9".to_owned(), "2001:db8::/32".to_owned()],
+                mac:           vec!["00:11:22:33:44:55".to_owned()],
+                country:       vec!["US".to_owned()],
+                ..Default::default()
+            }),
+        ], vec!["eve"]);
+
+        assert_eq!(Vec::<Violation>::new(), validate("c_test", &request, &dimensions()));
+    }
+
+    #[test]
+    fn validate_violations() {
+        let request = request(vec![
+            large("", Rules {
+                direction:     Some("up".to_owned()),
+                port:          vec!["22".to_owned(), "8080-80".to_owned(), "65536".to_owned()],
+                protocol:      vec![256],
+                asn:           vec!["AS64512".to_owned()],
+                vlans:         vec!["4096".to_owned()],
+                bgp_community: vec!["65000".to_owned()],
+                addr:          vec!["10.0.0.1/8".to_owned(), "10.0.0.9-::1".to_owned()],
+                mac:           vec!["00:11:22:33:44".to_owned()],
+                country:       vec!["XX".to_owned()],
+                site:          vec!["x".repeat(MAX_RULE_LEN + 1)],
+                ..Default::default()
+            }),
+            large("bob", Rules::default()),
+            Upsert::Large(Large { value: "carol".to_owned(), criteria: Vec::new() }),
+        ], vec!["eve"]);
+
+        let violations = validate("c_test", &request, &dimensions());
+        let paths      = violations.iter().map(|v| v.path.as_str()).collect::<Vec<_>>();
+
+        assert_eq!(vec![
+            "upserts[0].value",
+            "upserts[0].criteria[0].direction",
+            "upserts[0].criteria[0].protocol[0]",
+            "upserts[0].criteria[0].port[1]",
+            "upserts[0].criteria[0].port[2]",
+            "upserts[0].criteria[0].asn[0]",
+            "upserts[0].criteria[0].vlans[0]",
+            "upserts[0].criteria[0].bgp_community[0]",
+            "upserts[0].criteria[0].addr[0]",
+            "upserts[0].criteria[0].addr[1]",
+            "upserts[0].criteria[0].mac[0]",
+            "upserts[0].criteria[0].country[0]",
+            "upserts[0].criteria[0].site[0]",
+            "upserts[1].criteria[0]",
+            "upserts[2].criteria",
+        ], paths);
+    }
+
+    #[test]
+    fn validate_column() {
+        let request = request(vec![], vec!["42", "x"]);
+
+        let violations = validate("c_count", &request, &dimensions());
+        assert_eq!(vec![Violation {
+            path:    "deletes[1].value".to_owned(),
+            message: "x is not a uint32".to_owned(),
+        }], violations);
+
+        let violations = validate("Test", &request, &dimensions());
+        let messages   = violations.iter().map(Violation::to_string).collect::<Vec<_>>();
+        assert_eq!(vec![
+            "column: Test does not start with c_",
+            "column: Test contains invalid characters",
+            "column: unknown dimension Test",
+        ], messages);
+    }
+
+    fn dimensions() -> Vec<Dimension> {
+        vec![
+            Dimension { name: "c_test".to_owned(),  kind: "string".to_owned(), ..Default::default() },
+            Dimension { name: "c_count".to_owned(), kind: "uint32".to_owned(), ..Default::default() },
+        ]
+    }
+
+    fn request(upserts: Vec<Upsert>, deletes: Vec<&str>) -> Request {
+        Request {
+            replace_all: false,
+            complete:    true,
+            ttl_minutes: 0,
+            upserts,
+            deletes:     deletes.into_iter().map(|value| Delete { value: value.to_owned() }).collect(),
+            guid:        None,
+        }
+    }
+
+    fn small(value: &str, rule: tag::Rule) -> Upsert {
+        Upsert::Small(Small { value: value.to_owned(), criteria: (rule,) })
+    }
+
+    fn large(value: &str, rules: Rules) -> Upsert {
+        Upsert::Large(Large { value: value.to_owned(), criteria: vec![rules] })
+    }
+}
@@ -0,0 +1,54 @@
+mod server;
+
+use std::time::Duration;
+use kentik_api::Client;
+use kentik_api::alert::*;
+use server::Server;
+
+#[test]
+fn get_alert_policies() {
+    let (client, _server) = pair();
+
+    let policies  = client.get_alert_policies().unwrap();
+    let policy    = &policies[0];
+    let threshold = &policy.thresholds[0];
+
+    assert_eq!(vec!["IP_dst".to_owned()], policy.dimensions);
+    assert_eq!(Severity::Critical, threshold.severity);
+    assert_eq!(Comparison::GreaterThan, threshold.conditions[0].comparison);
+    assert_eq!(1e9, threshold.conditions[0].value);
+    assert_eq!(7, policy.baseline.as_ref().unwrap().lookback_days);
+    assert_eq!(3, policy.activation.times);
+}
+
+#[test]
+fn create_notification_channel() {
+    let (client, server) = pair();
+    let timeout = Duration::from_secs(1);
+
+    let channel = Channel {
+        id:      String::new(),
+        name:    "on-call".to_owned(),
+        enabled: true,
+        target:  Target::PagerDuty(PagerDuty {
+            routing_key: "abc".to_owned(),
+        }),
+    };
+
+    let created = client.create_notification_channel(&channel).unwrap();
+    assert_eq!("1", created.id);
+    assert_eq!(channel.target, created.target);
+
+    let request = server.request(timeout).unwrap();
+    let body    = serde_json::from_slice::<serde_json::Value>(&request.body()).unwrap();
+    assert_eq!("pagerduty", body["channel"]["type"]);
+    assert_eq!("abc", body["channel"]["config"]["routingKey"]);
+}
+
+fn pair() -> (Client, Server) {
+    let server = server::start("127.0.0.1:0", None, None);
+    let (email, token) = server.auth();
+    let endpoint = server.url("");
+    let client = Client::new(&email, &token, &endpoint, None).unwrap();
+    (client, server)
+}
@@ -0,0 +1,73 @@
+mod server;
+
+use std::time::Duration;
+use chrono::{TimeZone, Utc};
+use kentik_api::Client;
+use kentik_api::bgp::*;
+use server::Server;
+
+#[test]
+fn get_bgp_monitors() {
+    let (client, _server) = pair();
+
+    let monitors = client.get_bgp_monitors().unwrap();
+    let settings = &monitors[0].settings;
+
+    assert_eq!(MonitorStatus::Active, monitors[0].status);
+    assert_eq!("192.0.2.0/24".parse().ok(), settings.targets.first().copied());
+    assert_eq!(vec![64512, 64513], settings.allowed_asns);
+    assert!(settings.check_rpki);
+}
+
+#[test]
+fn get_bgp_metrics() {
+    let (client, server) = pair();
+    let timeout = Duration::from_secs(1);
+
+    let query = MetricsQuery {
+        start_time:      Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap(),
+        end_time:        Utc.with_ymd_and_hms(2026, 1, 2, 0, 0, 0).unwrap(),
+        prefix:          "192.0.2.0/24".parse().unwrap(),
+        include_covered: false,
+        metrics:         vec![MetricKind::Reachability, MetricKind::PathChanges],
+    };
+
+    let data    = client.get_bgp_metrics(&query).unwrap();
+    let metrics = data[0].metrics.iter().map(|m| (m.kind, m.value)).collect::<Vec<_>>();
+
+    assert_eq!(query.prefix, data[0].prefix);
+    assert_eq!(vec![(MetricKind::Reachability, 98.5), (MetricKind::PathChanges, 3.0)], metrics);
+
+    let request = server.request(timeout).unwrap();
+    let body    = serde_json::from_slice::<serde_json::Value>(&request.body()).unwrap();
+    assert_eq!("192.0.2.0/24", body["prefix"]);
+    assert_eq!("METRIC_TYPE_PATH_CHANGES", body["metrics"][1]);
+}
+
+#[test]
+fn get_bgp_routes() {
+    let (client, _server) = pair();
+
+    let query = RoutesQuery {
+        prefix:          "192.0.2.0/24".parse().unwrap(),
+        include_covered: true,
+        check_rpki:      true,
+        time:            None,
+    };
+
+    let routes = client.get_bgp_routes(&query).unwrap();
+
+    assert_eq!(RpkiStatus::Valid, routes[0].rpki_status);
+    assert_eq!(Some("198.51.100.1".parse().unwrap()), routes[0].next_hop);
+    assert_eq!("rrc00", routes[0].vantage_point.collector);
+    assert_eq!(RpkiStatus::Unknown, routes[1].rpki_status);
+    assert_eq!(64666, routes[1].origin_asn);
+}
+
+fn pair() -> (Client, Server) {
+    let server = server::start("127.0.0.1:0", None, None);
+    let (email, token) = server.auth();
+    let endpoint = server.url("");
+    let client = Client::new(&email, &token, &endpoint, None).unwrap();
+    (client, server)
+}
@@ -0,0 +1,81 @@
+mod server;
+
+use std::time::Duration;
+use kentik_api::Client;
+use kentik_api::cloud::*;
+use server::Server;
+
+#[test]
+fn get_cloud_exports() {
+    let (client, _server) = pair();
+
+    let exports = client.get_cloud_exports().unwrap();
+
+    let aws = Aws {
+        bucket:       "flows".to_owned(),
+        iam_role_arn: "arn:aws:iam::1:role/kentik".to_owned(),
+        region:       "us-east-1".to_owned(),
+        ..Default::default()
+    };
+
+    let gcp = Gcp {
+        project:      "net".to_owned(),
+        subscription: "flows".to_owned(),
+    };
+
+    assert_eq!(Provider::Aws(aws), exports[0].provider);
+    assert_eq!(Provider::Gcp(gcp), exports[1].provider);
+    assert_eq!(ExportKind::KentikManaged, exports[0].kind);
+    assert!(exports[0].status.as_ref().unwrap().flow_found);
+    assert_eq!(None, exports[1].status);
+}
+
+#[test]
+fn create_cloud_export() {
+    let (client, server) = pair();
+    let timeout = Duration::from_secs(1);
+
+    let export = Export {
+        id:          String::new(),
+        name:        "azure-flow".to_owned(),
+        description: String::new(),
+        kind:        ExportKind::CustomerManaged,
+        enabled:     true,
+        plan_id:     "10".to_owned(),
+        provider:    Provider::Azure(Azure {
+            location:        "eastus".to_owned(),
+            resource_group:  "network".to_owned(),
+            storage_account: "flowlogs".to_owned(),
+            subscription_id: "0000".to_owned(),
+            ..Default::default()
+        }),
+        status:      None,
+    };
+
+    let created = client.create_cloud_export(&export).unwrap();
+    assert_eq!("3", created.id);
+    assert_eq!(export.provider, created.provider);
+
+    let request = server.request(timeout).unwrap();
+    let body    = serde_json::from_slice::<serde_json::Value>(&request.body()).unwrap();
+    assert_eq!("azure", body["export"]["cloudProvider"]);
+    assert_eq!("flowlogs", body["export"]["azure"]["storageAccount"]);
+    assert!(body["export"].get("aws").is_none());
+}
+
+#[test]
+fn invalid_cloud_export() {
+    let json = r#"{"name": "x", "cloudProvider": "aws"}"#;
+    assert!(serde_json::from_str::<Export>(json).is_err());
+
+    let json = r#"{"name": "x", "cloudProvider": "oracle"}"#;
+    assert!(serde_json::from_str::<Export>(json).is_err());
+}
+
+fn pair() -> (Client, Server) {
+    let server = server::start("127.0.0.1:0", None, None);
+    let (email, token) = server.auth();
+    let endpoint = server.url("");
+    let client = Client::new(&email, &token, &endpoint, None).unwrap();
+    (client, server)
+}
@@ -19,7 +19,7 @@ fn send_dns_batch() {
 
         Response {
             question: Question {
-                name: name,
+                name,
                 host: host.octets().to_vec(),
             },
             answers: vec![Answer{
@@ -0,0 +1,61 @@
+mod server;
+
+use std::time::Duration;
+use kentik_api::Client;
+use kentik_api::kmi::*;
+use server::Server;
+
+#[test]
+fn get_kmi_markets() {
+    let (client, _server) = pair();
+
+    let markets = client.get_kmi_markets().unwrap();
+
+    assert_eq!("de", markets[0].id);
+    assert_eq!("Germany", markets[0].name);
+}
+
+#[test]
+fn get_kmi_rankings() {
+    let (client, server) = pair();
+    let timeout = Duration::from_secs(1);
+
+    let query = RankingsQuery {
+        rank_type:  RankKind::CustomerBase,
+        ip_version: IpVersion::Ipv4,
+        limit:      Some(2),
+    };
+
+    let rankings = client.get_kmi_rankings("de", &query).unwrap();
+    let ranks    = rankings.iter().map(|r| (r.rank, r.asn)).collect::<Vec<_>>();
+
+    assert_eq!(vec![(1, 3320), (2, 1299)], ranks);
+    assert_eq!("de-first", rankings[0].name);
+    assert_eq!(-1, rankings[1].rank_change);
+
+    let request = server.request(timeout).unwrap();
+    let body    = serde_json::from_slice::<serde_json::Value>(&request.body()).unwrap();
+    assert_eq!("/kmi/v202212/market/de/rankings", request.path);
+    assert_eq!("customer_base", body["rankType"]);
+    assert_eq!("ipv4", body["ipVersion"]);
+}
+
+#[test]
+fn get_kmi_asn_details() {
+    let (client, _server) = pair();
+
+    let details = client.get_kmi_asn_details(3320, "de", IpVersion::Ipv6).unwrap();
+
+    assert_eq!(3320, details.asn);
+    assert_eq!(vec![64512], details.customers.iter().map(|r| r.asn).collect::<Vec<_>>());
+    assert_eq!(vec![1299], details.providers.iter().map(|r| r.asn).collect::<Vec<_>>());
+    assert!(details.peers.is_empty());
+}
+
+fn pair() -> (Client, Server) {
+    let server = server::start("127.0.0.1:0", None, None);
+    let (email, token) = server.auth();
+    let endpoint = server.url("");
+    let client = Client::new(&email, &token, &endpoint, None).unwrap();
+    (client, server)
+}
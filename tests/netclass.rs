@@ -0,0 +1,51 @@
+mod server;
+
+use std::time::Duration;
+use kentik_api::Client;
+use kentik_api::netclass::*;
+use server::Server;
+
+#[test]
+fn get_network_class() {
+    let (client, _server) = pair();
+
+    let nc = NetworkClass {
+        internal_ips:  vec!["10.0.0.0/8".parse().unwrap()],
+        internal_asns: vec![64512],
+        ..Default::default()
+    };
+
+    assert_eq!(Ok(nc), client.get_network_class());
+}
+
+#[test]
+fn update_network_class() {
+    let (client, server) = pair();
+    let timeout = Duration::from_secs(1);
+
+    let nc = NetworkClass {
+        internal_ips:      vec!["192.168.0.0/16".parse().unwrap()],
+        use_internal_asns: true,
+        cloud_subnets:     vec![CloudSubnets {
+            kind:    CloudType::Aws,
+            subnets: vec!["172.16.0.0/12".parse().unwrap()],
+        }],
+        ..Default::default()
+    };
+
+    assert_eq!(Ok(nc.clone()), client.update_network_class(&nc));
+
+    let request = server.request(timeout).unwrap();
+    let body    = serde_json::from_slice::<serde_json::Value>(&request.body()).unwrap();
+    assert_eq!("PUT", request.method.as_str());
+    assert_eq!("192.168.0.0/16", body["networkClass"]["internalIps"][0]);
+    assert_eq!("CLOUD_TYPE_AWS", body["networkClass"]["cloudSubnets"][0]["type"]);
+}
+
+fn pair() -> (Client, Server) {
+    let server = server::start("127.0.0.1:0", None, None);
+    let (email, token) = server.auth();
+    let endpoint = server.url("");
+    let client = Client::new(&email, &token, &endpoint, None).unwrap();
+    (client, server)
+}
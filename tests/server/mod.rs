@@ -1,8 +1,8 @@
 #![allow(dead_code, non_snake_case)]
 
 use std::marker::PhantomData;
-use std::mem::replace;
 use std::net::{SocketAddr, ToSocketAddrs};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::thread;
 use std::time::{Instant, Duration};
 use actix_rt::System;
@@ -17,7 +17,11 @@ use futures_old::{Async, Future, Poll, Stream};
 use futures_old::future::{ok, FutureResult};
 use rand::prelude::*;
 use serde::{Serialize, Deserialize};
-use kentik_api::core::{Device, Dimension};
+use kentik_api::alert::Channel;
+use kentik_api::cloud::Export;
+use kentik_api::core::{Device, Dimension, Dimensions, Populator};
+use kentik_api::netclass::NetworkClass;
+use kentik_api::synth::{Agent, AgentKind, Test};
 
 pub struct Server {
     address:  SocketAddr,
@@ -55,8 +59,44 @@ pub fn start<T: ToSocketAddrs + Send + 'static>(addrs: T, email: Option<&str>, t
                 .wrap(auth.clone())
                 .service(get_device)
                 .service(resource("/api/internal/customdimension").route(post().to(add_custom_dimension)))
+                .service(resource("/api/internal/customdimensions").route(get().to(get_custom_dimensions)))
+                .service(resource("/api/internal/customdimension/{column}/populators").route(get().to(get_populators)))
+                .service(resource("/api/internal/batch/customdimensions/{column}/populators").route(post().data(JsonConfig::default().limit(1 << 24)).to(update_populators)))
                 .service(resource("/dns").route(post().to_async(dns_batch)))
-        }).bind(addrs).unwrap();
+                .service(resource("/network_class/v202109beta1/network_class")
+                         .route(get().to(get_network_class))
+                         .route(put().to(update_network_class)))
+                .service(resource("/synthetics/v202202/tests")
+                         .route(get().to(get_synthetic_tests))
+                         .route(post().to(create_synthetic_test)))
+                .service(resource("/synthetics/v202202/tests/{id}")
+                         .route(delete().to(delete_synthetic_test)))
+                .service(resource("/synthetics/v202202/agents")
+                         .route(get().to(get_synthetic_agents)))
+                .service(resource("/alert_policy/v202303/policies")
+                         .route(get().to(get_alert_policies)))
+                .service(resource("/notification/v202210/channels")
+                         .route(post().to(create_notification_channel)))
+                .service(resource("/bgp_monitoring/v202205beta1/monitors")
+                         .route(get().to(get_bgp_monitors)))
+                .service(resource("/bgp_monitoring/v202205beta1/metrics")
+                         .route(post().to(get_bgp_metrics)))
+                .service(resource("/bgp_monitoring/v202205beta1/routes")
+                         .route(post().to(get_bgp_routes)))
+                .service(resource("/cloud_export/v202210/exports")
+                         .route(get().to(get_cloud_exports))
+                         .route(post().to(create_cloud_export)))
+                .service(resource("/kmi/v202212/markets")
+                         .route(get().to(get_kmi_markets)))
+                .service(resource("/kmi/v202212/market/{id}/rankings")
+                         .route(post().to(get_kmi_rankings)))
+                .service(resource("/kmi/v202212/asn/{asn}")
+                         .route(post().to(get_kmi_asn_details)))
+                .service(resource("/synthetics/v202202/results")
+                         .route(post().to(get_synthetic_results)))
+                .service(resource("/synthetics/v202202/trace")
+                         .route(post().to(get_synthetic_trace)))
+        }).workers(4).bind(addrs).unwrap();
         let address = server.addrs()[0];
         let server  = server.start();
         tx0.send((address, server)).unwrap();
@@ -66,10 +106,10 @@ pub fn start<T: ToSocketAddrs + Send + 'static>(addrs: T, email: Option<&str>, t
     let (address, server) = rx0.recv().unwrap();
 
     Server {
-        address:  address,
-        server:   server,
-        email:    email,
-        token:    token,
+        address,
+        server,
+        email,
+        token,
         requests: rx1,
     }
 }
@@ -88,7 +128,7 @@ impl Server {
     }
 
     pub fn stop(&self) {
-        self.server.stop(false);
+        let _ = self.server.stop(false);
     }
 }
 
@@ -130,7 +170,7 @@ impl<S, P, B> Transform<S> for Auth where
         ok(AuthMiddleware {
             email:   self.email.parse().unwrap(),
             token:   self.token.parse().unwrap(),
-            service: service,
+            service,
         })
     }
 }
@@ -210,7 +250,7 @@ impl<P> Service for Inspector<P> where P: Stream<Item = Bytes, Error = PayloadEr
 
     fn call(&mut self, req: ServiceRequest<P>) -> Self::Future {
         let (req, payload) = req.into_parts();
-        let (tx, rx) = bounded(1);
+        let (tx, rx) = unbounded();
         self.requests.send(Request {
             method: req.method().clone(),
             path:   req.path().to_string(),
@@ -230,7 +270,7 @@ pub struct Body<S> {
 impl<S> Body<S> where S: Stream<Item = Bytes, Error = PayloadError> {
     pub fn new(stream: S, tx: Sender<Bytes>) -> Self {
         Self {
-            stream: stream,
+            stream,
             chunks: tx,
         }
     }
@@ -245,7 +285,7 @@ impl<S> Stream for Body<S> where S: Stream<Item = Bytes, Error = PayloadError> {
         if let Async::Ready(ready) = &poll {
             match ready {
                 Some(bytes) => { self.chunks.try_send(bytes.clone()).ok(); },
-                None        => { replace(&mut self.chunks, bounded(1).0);  },
+                None        => { self.chunks = bounded(1).0;               },
             };
         }
         Ok(poll)
@@ -290,18 +330,326 @@ enum DimensionWrapper {
     Error{error: String},
 }
 
+fn get_custom_dimensions() -> Json<Dimensions> {
+    let dimension = |id: u64, name: &str, kind: &str| Dimension {
+        id,
+        name: name.to_owned(),
+        kind: kind.to_owned(),
+        ..Default::default()
+    };
+
+    Json(Dimensions {
+        dimensions: vec![
+            dimension(1, "c_test",  "string"),
+            dimension(2, "c_count", "uint32"),
+        ],
+    })
+}
+
 fn add_custom_dimension(json: Json<Dimension>) -> Json<DimensionWrapper> {
     Json(DimensionWrapper::Dimension{
         customDimension: json.into_inner(),
     })
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+struct NetworkClassWrapper {
+    networkClass: NetworkClass,
+}
+
+fn get_network_class() -> Json<NetworkClassWrapper> {
+    Json(NetworkClassWrapper {
+        networkClass: NetworkClass {
+            internal_ips:  vec!["10.0.0.0/8".parse().unwrap()],
+            internal_asns: vec![64512],
+            ..Default::default()
+        },
+    })
+}
+
+fn update_network_class(json: Json<NetworkClassWrapper>) -> Json<NetworkClassWrapper> {
+    json
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct TestWrapper {
+    test: Test,
+}
+
+fn get_synthetic_tests() -> HttpResponse {
+    HttpResponse::Ok().json(serde_json::json!({
+        "tests": [{
+            "id":       "1",
+            "name":     "ping",
+            "type":     "ip",
+            "status":   "TEST_STATUS_ACTIVE",
+            "settings": {
+                "ip":       {"targets": ["10.0.0.1"]},
+                "agentIds": ["2"],
+                "tasks":    ["ping", "traceroute"],
+                "period":   60,
+            },
+        }],
+        "invalidTestsCount": 0,
+    }))
+}
+
+fn create_synthetic_test(json: Json<TestWrapper>) -> Json<TestWrapper> {
+    let mut wrapper = json.into_inner();
+    wrapper.test.id = "1".to_owned();
+    Json(wrapper)
+}
+
+fn delete_synthetic_test(id: Path<String>) -> HttpResponse {
+    match id.as_str() {
+        "404" => HttpResponse::NotFound().finish(),
+        _     => HttpResponse::Ok().json(serde_json::json!({})),
+    }
+}
+
+fn get_synthetic_agents() -> HttpResponse {
+    HttpResponse::Ok().json(serde_json::json!({
+        "agents": [
+            Agent{id: "1".to_owned(), alias: "public".to_owned(),  ..Default::default()},
+            Agent{id: "2".to_owned(), alias: "private".to_owned(), kind: AgentKind::Private, ..Default::default()},
+        ],
+    }))
+}
+
+fn get_kmi_markets() -> HttpResponse {
+    HttpResponse::Ok().json(serde_json::json!({
+        "markets": [{"id": "de", "name": "Germany", "marketType": "country"}],
+    }))
+}
+
+fn get_kmi_rankings(id: Path<String>, _: Json<serde_json::Value>) -> HttpResponse {
+    HttpResponse::Ok().json(serde_json::json!({
+        "rankings": [
+            {"asn": 3320, "name": format!("{}-first", id), "rank": 1, "rankChange": 0,  "score": 100.0},
+            {"asn": 1299, "name": format!("{}-second", id), "rank": 2, "rankChange": -1, "score": 90.5},
+        ],
+    }))
+}
+
+fn get_kmi_asn_details(asn: Path<u32>, _: Json<serde_json::Value>) -> HttpResponse {
+    HttpResponse::Ok().json(serde_json::json!({
+        "asnDetails": {
+            "asn":       *asn,
+            "name":      "EXAMPLE",
+            "country":   "DE",
+            "customers": [{"asn": 64512, "name": "CUSTOMER", "country": "DE"}],
+            "providers": [{"asn": 1299, "name": "PROVIDER", "country": "SE"}],
+        },
+    }))
+}
+
+fn get_synthetic_results(_: Json<serde_json::Value>) -> HttpResponse {
+    HttpResponse::Ok().json(serde_json::json!({
+        "results": [{
+            "testId": "1",
+            "time":   "2026-01-01T00:00:00Z",
+            "health": "warning",
+            "agents": [{
+                "agentId": "2",
+                "health":  "warning",
+                "tasks":   [{
+                    "ping": {
+                        "target":     "10.0.0.1",
+                        "packetLoss": {"current": 0.5, "health": "warning"},
+                        "latency":    {"current": 1500.0, "rollingAvg": 1200.0, "rollingStddev": 100.0, "health": "healthy"},
+                        "jitter":     {"current": 20.0, "health": "healthy"},
+                        "dstIp":      "10.0.0.1",
+                    },
+                    "health": "warning",
+                }],
+            }],
+        }],
+    }))
+}
+
+fn get_synthetic_trace(_: Json<serde_json::Value>) -> HttpResponse {
+    HttpResponse::Ok().json(serde_json::json!({
+        "nodes": {
+            "n1": {"ip": "192.168.0.1", "asn": 64512, "asName": "EXAMPLE"},
+            "n2": {"ip": "10.0.0.1"},
+        },
+        "paths": [{
+            "agentId":  "2",
+            "targetIp": "10.0.0.1",
+            "hopCount": {"average": 2.0, "min": 2, "max": 2},
+            "traces":   [{
+                "asPath":     [64512],
+                "isComplete": true,
+                "hops":       [{"latency": 100.0, "nodeId": "n1"}, {"latency": 200.0, "nodeId": "n2"}],
+            }],
+            "time":     "2026-01-01T00:00:00Z",
+        }],
+    }))
+}
+
+fn get_alert_policies() -> HttpResponse {
+    HttpResponse::Ok().json(serde_json::json!({
+        "policies": [{
+            "id":         "1",
+            "name":       "ddos",
+            "enabled":    true,
+            "dimensions": ["IP_dst"],
+            "metrics":    ["bits"],
+            "thresholds": [{
+                "severity":   "SEVERITY_CRITICAL",
+                "conditions": [{"metric": "bits", "comparison": "COMPARISON_GREATER_THAN", "value": 1e9}],
+            }],
+            "baseline":   {"lookbackDays": 7, "aggregation": "p95"},
+            "activation": {"timeWindow": 300, "times": 3},
+        }],
+    }))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct ChannelWrapper {
+    channel: Channel,
+}
+
+fn create_notification_channel(json: Json<ChannelWrapper>) -> Json<ChannelWrapper> {
+    let mut wrapper = json.into_inner();
+    wrapper.channel.id = "1".to_owned();
+    Json(wrapper)
+}
+
+fn get_bgp_monitors() -> HttpResponse {
+    HttpResponse::Ok().json(serde_json::json!({
+        "monitors": [{
+            "id":       "1",
+            "name":     "anycast",
+            "status":   "BGP_MONITOR_STATUS_ACTIVE",
+            "settings": {
+                "targets":     ["192.0.2.0/24", "2001:db8::/32"],
+                "allowedAsns": [64512, 64513],
+                "checkRpki":   true,
+            },
+        }],
+    }))
+}
+
+fn get_bgp_metrics(_: Json<serde_json::Value>) -> HttpResponse {
+    HttpResponse::Ok().json(serde_json::json!({
+        "data": [{
+            "prefix":  "192.0.2.0/24",
+            "metrics": [
+                {"time": "2026-01-01T00:00:00Z", "type": "METRIC_TYPE_REACHABILITY", "value": 98.5},
+                {"time": "2026-01-01T00:00:00Z", "type": "METRIC_TYPE_PATH_CHANGES", "value": 3.0},
+            ],
+        }],
+    }))
+}
+
+fn get_bgp_routes(_: Json<serde_json::Value>) -> HttpResponse {
+    HttpResponse::Ok().json(serde_json::json!({
+        "routes": [{
+            "prefix":       "192.0.2.0/24",
+            "asPath":       [64500, 64512],
+            "originAsn":    64512,
+            "nextHop":      "198.51.100.1",
+            "rpkiStatus":   "RPKI_STATUS_VALID",
+            "vantagePoint": {"collector": "rrc00", "peerAsn": 64500},
+        }, {
+            "prefix":     "192.0.2.0/25",
+            "asPath":     [64500, 64666],
+            "originAsn":  64666,
+            "rpkiStatus": "RPKI_STATUS_INVALID_ASN",
+        }],
+    }))
+}
+
+fn get_cloud_exports() -> HttpResponse {
+    HttpResponse::Ok().json(serde_json::json!({
+        "exports": [{
+            "id":            "1",
+            "type":          "CLOUD_EXPORT_TYPE_KENTIK_MANAGED",
+            "enabled":       true,
+            "name":          "vpc-flow",
+            "planId":        "10",
+            "cloudProvider": "aws",
+            "aws":           {"bucket": "flows", "iamRoleArn": "arn:aws:iam::1:role/kentik", "region": "us-east-1"},
+            "currentStatus": {"status": "OK", "flowFound": true},
+        }, {
+            "id":            "2",
+            "name":          "gcp-flow",
+            "cloudProvider": "gce",
+            "gce":           {"project": "net", "subscription": "flows"},
+        }],
+    }))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct ExportWrapper {
+    export: Export,
+}
+
+fn create_cloud_export(json: Json<ExportWrapper>) -> Json<ExportWrapper> {
+    let mut wrapper = json.into_inner();
+    wrapper.export.id = "3".to_owned();
+    Json(wrapper)
+}
+
+fn get_populators(column: Path<String>) -> HttpResponse {
+    let populator = |value: &str, addr: &str| Populator {
+        value: value.to_owned(),
+        addr:  addr.to_owned(),
+        ..Default::default()
+    };
+
+    match column.as_str() {
+        "c_test" => HttpResponse::Ok().json(serde_json::json!({
+            "populators": [
+                populator("alice", "10.0.0.16"),
+                populator("bob",   "10.0.0.32"),
+                populator("eve",   "10.0.0.48"),
+            ],
+        })),
+        "c_503" => HttpResponse::ServiceUnavailable().finish(),
+        _ => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "unknown column",
+        })),
+    }
+}
+
+fn update_populators(column: Path<String>, body: Json<serde_json::Value>) -> HttpResponse {
+    let guid = match body.get("guid").and_then(|v| v.as_str()) {
+        Some(guid) => guid.to_owned(),
+        None       => random(),
+    };
+
+    match column.as_str() {
+        "c_invalid" => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "invalid column",
+        })),
+        "c_503" => HttpResponse::ServiceUnavailable().finish(),
+        "c_flaky" if FLAKY.fetch_add(1, Ordering::SeqCst) < 4 => {
+            HttpResponse::ServiceUnavailable().finish()
+        },
+        "c_slow" => {
+            thread::sleep(Duration::from_millis(500));
+            HttpResponse::Ok().json(serde_json::json!({
+                "message": "ok",
+                "guid":    guid,
+            }))
+        },
+        _ => HttpResponse::Ok().json(serde_json::json!({
+            "message": "ok",
+            "guid":    guid,
+        })),
+    }
+}
+
 fn dns_batch(p: web::Payload) -> impl Future<Item = HttpResponse, Error = Error> {
     p.concat2().from_err().and_then(|_body| {
         Ok(HttpResponse::Ok().finish())
     })
 }
 
+static FLAKY: AtomicUsize = AtomicUsize::new(0);
+
 fn random() -> String {
     let mut rng  = rand::thread_rng();
     let mut data = [0u8; 8];
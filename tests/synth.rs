@@ -0,0 +1,144 @@
+mod server;
+
+use std::time::Duration;
+use chrono::{TimeZone, Utc};
+use kentik_api::{Client, Error};
+use kentik_api::synth::*;
+use server::Server;
+
+#[test]
+fn get_synthetic_tests() {
+    let (client, _server) = pair();
+
+    let tests = client.get_synthetic_tests().unwrap();
+    let test  = &tests[0];
+
+    assert_eq!(1, tests.len());
+    assert_eq!("1", test.id);
+    assert_eq!(TestKind::Ip, test.kind);
+    assert_eq!(TestStatus::Active, test.status);
+    assert_eq!(vec![Task::Ping, Task::Traceroute], test.settings.tasks);
+    assert_eq!(Some(IpTarget{targets: vec!["10.0.0.1".parse().unwrap()]}), test.settings.ip);
+    assert_eq!(60, test.settings.period);
+}
+
+#[test]
+fn create_synthetic_test() {
+    let (client, server) = pair();
+    let timeout = Duration::from_secs(1);
+
+    let test = Test {
+        id:       String::new(),
+        name:     "example.com".to_owned(),
+        kind:     TestKind::Hostname,
+        status:   TestStatus::Active,
+        settings: Settings {
+            hostname:        Some(HostnameTarget{target: "example.com".to_owned()}),
+            agent_ids:       vec!["2".to_owned()],
+            tasks:           vec![Task::Ping],
+            health_settings: HealthSettings {
+                latency_critical: 100.0,
+                latency_warning:  50.0,
+                ..Default::default()
+            },
+            period:          60,
+            ..Default::default()
+        },
+    };
+
+    let created = client.create_synthetic_test(&test).unwrap();
+    assert_eq!("1", created.id);
+    assert_eq!(test.settings, created.settings);
+
+    let request = server.request(timeout).unwrap();
+    let body    = serde_json::from_slice::<serde_json::Value>(&request.body()).unwrap();
+    assert_eq!("hostname", body["test"]["type"]);
+    assert_eq!(100.0, body["test"]["settings"]["healthSettings"]["latencyCritical"]);
+    assert!(body["test"].get("id").is_none());
+}
+
+#[test]
+fn delete_synthetic_test() {
+    let (client, server) = pair();
+    let timeout = Duration::from_secs(1);
+
+    assert_eq!(Ok(()), client.delete_synthetic_test("1"));
+
+    let request = server.request(timeout).unwrap();
+    assert_eq!("DELETE", request.method.as_str());
+    assert_eq!("/synthetics/v202202/tests/1", request.path);
+
+    assert_eq!(Err(Error::Status(404)), client.delete_synthetic_test("404"));
+}
+
+#[test]
+fn get_synthetic_agents() {
+    let (client, _server) = pair();
+
+    let agents = client.get_synthetic_agents().unwrap();
+    let kinds  = agents.iter().map(|a| a.kind).collect::<Vec<_>>();
+
+    assert_eq!(vec![AgentKind::Global, AgentKind::Private], kinds);
+}
+
+#[test]
+fn get_synthetic_results() {
+    let (client, server) = pair();
+    let timeout = Duration::from_secs(1);
+
+    let query = ResultsQuery {
+        ids:        vec!["1".to_owned()],
+        start_time: Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap(),
+        end_time:   Utc.with_ymd_and_hms(2026, 1, 2, 0, 0, 0).unwrap(),
+        agent_ids:  vec![],
+        aggregate:  false,
+    };
+
+    let results = client.get_synthetic_results(&query).unwrap();
+    let agent   = &results[0].agents[0];
+    let ping    = agent.tasks[0].ping.as_ref().unwrap();
+
+    assert_eq!(query.start_time, results[0].time);
+    assert_eq!(Health::Warning, agent.health);
+    assert_eq!(0.5, ping.packet_loss.current);
+    assert_eq!(1500.0, ping.latency.current);
+    assert_eq!(20.0, ping.jitter.current);
+    assert_eq!(None, agent.tasks[0].dns);
+
+    let request = server.request(timeout).unwrap();
+    let body    = serde_json::from_slice::<serde_json::Value>(&request.body()).unwrap();
+    assert_eq!("2026-01-01T00:00:00Z", body["startTime"]);
+    assert!(body.get("agentIds").is_none());
+}
+
+#[test]
+fn get_synthetic_trace() {
+    let (client, _server) = pair();
+
+    let query = TraceQuery {
+        id:         "1".to_owned(),
+        start_time: Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap(),
+        end_time:   Utc.with_ymd_and_hms(2026, 1, 2, 0, 0, 0).unwrap(),
+        agent_ids:  vec!["2".to_owned()],
+        target_ips: vec!["10.0.0.1".parse().unwrap()],
+    };
+
+    let trace = client.get_synthetic_trace(&query).unwrap();
+    let path  = &trace.paths[0];
+    let hops  = path.traces[0].hops.iter().map(|hop| {
+        trace.nodes[&hop.node_id].ip.as_str()
+    }).collect::<Vec<_>>();
+
+    assert_eq!(vec!["192.168.0.1", "10.0.0.1"], hops);
+    assert_eq!(64512, trace.nodes["n1"].asn);
+    assert_eq!(2, path.hop_count.max);
+    assert!(path.traces[0].is_complete);
+}
+
+fn pair() -> (Client, Server) {
+    let server = server::start("127.0.0.1:0", None, None);
+    let (email, token) = server.auth();
+    let endpoint = server.url("");
+    let client = Client::new(&email, &token, &endpoint, None).unwrap();
+    (client, server)
+}
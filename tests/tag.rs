@@ -0,0 +1,512 @@
+mod server;
+
+use std::{env, process};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use kentik_api::{Client, Error};
+use kentik_api::tag::*;
+use kentik_api::tag::change::{batch, delete, upsert, Change, Rule::*};
+use server::Server;
+
+#[test]
+fn reconcile_populators() {
+    let (client, server) = pair();
+    let timeout = Duration::from_secs(1);
+
+    let mut change = upsert("c_test");
+    change.value("alice").when(IP("10.0.0.16".parse().unwrap()));
+    change.value("bob").when(IP("10.0.0.33".parse().unwrap()));
+    change.value("mallory").when(IP("10.0.0.64".parse().unwrap()));
+    let (column, desired): (String, Vec<Upsert>) = change.into();
+
+    assert!(client.reconcile_populators(&column, desired).unwrap().is_some());
+
+    let request = server.request(timeout).unwrap();
+    assert_eq!("/api/internal/customdimension/c_test/populators", request.path);
+
+    let request = server.request(timeout).unwrap();
+    let body    = serde_json::from_slice::<Request>(&request.body()).unwrap();
+    let values  = body.upserts.iter().map(Upsert::value).collect::<Vec<_>>();
+
+    assert_eq!("/api/internal/batch/customdimensions/c_test/populators", request.path);
+    assert_eq!(vec!["bob", "mallory"], values);
+    assert_eq!(vec![Delete{value: "eve".to_owned()}], body.deletes);
+    assert!(!body.replace_all);
+}
+
+#[test]
+fn reconcile_unchanged() {
+    let (client, server) = pair();
+    let timeout = Duration::from_millis(100);
+
+    let mut change = upsert("c_test");
+    change.value("alice").when(IP("10.0.0.16".parse().unwrap()));
+    change.value("bob").when(IP("10.0.0.32".parse().unwrap()));
+    change.value("eve").when(IP("10.0.0.48".parse().unwrap()));
+    let (column, desired): (String, Vec<Upsert>) = change.into();
+
+    assert_eq!(None, client.reconcile_populators(&column, desired).unwrap());
+
+    assert!(server.request(timeout).is_ok());
+    assert!(server.request(timeout).is_err());
+}
+
+#[test]
+fn update_populators_in_chunks() {
+    let (client, server) = pair();
+    let timeout = Duration::from_secs(1);
+
+    let mut change = upsert("c_test");
+    for n in 0..BATCH_LIMIT + 10 {
+        change.value(&format!("v{}", n)).when(Port(n as u16));
+    }
+    let (column, upserts): (String, Vec<Upsert>) = change.into();
+
+    let request = Request {
+        replace_all: true,
+        complete:    true,
+        ttl_minutes: 0,
+        upserts,
+        deletes:     vec![Delete{value: "eve".to_owned()}],
+        guid:        None,
+    };
+
+    let response = client.update_populators(&column, &request).unwrap();
+
+    let first = server.request(timeout).unwrap();
+    let first = serde_json::from_slice::<Request>(&first.body()).unwrap();
+    let last  = server.request(timeout).unwrap();
+    let last  = serde_json::from_slice::<Request>(&last.body()).unwrap();
+
+    assert_eq!(BATCH_LIMIT, first.len());
+    assert_eq!(11, last.len());
+    assert_eq!((None, false), (first.guid, first.complete));
+    assert_eq!((Some(response.guid), true), (last.guid, last.complete));
+    assert!(first.replace_all && last.replace_all);
+    assert_eq!(request.deletes, last.deletes);
+    assert!(server.request(Duration::from_millis(100)).is_err());
+}
+
+#[test]
+fn client_delivery_results() {
+    let (client, _server) = pair();
+    let client  = kentik_api::tag::Client::new(client);
+    let timeout = Duration::from_secs(1);
+
+    let mut change = upsert("c_test");
+    change.value("alice").when(IP("10.0.0.16".parse().unwrap()));
+    let request = request(change);
+
+    let ok  = client.send("c_test", request.clone(), timeout).unwrap();
+    let err = client.send("c_invalid", request, timeout).unwrap();
+
+    assert!(ok.wait_timeout(timeout).is_ok());
+    assert!(matches!(err.wait_timeout(timeout), Err(Error::App(_, 400))));
+    assert_eq!(Stats{success: 1, failure: 1}, client.stats());
+
+    client.stop().unwrap();
+}
+
+#[test]
+fn client_coalesce_column() {
+    let (client, server) = pair();
+    let window  = Some(Duration::from_millis(200));
+    let client  = kentik_api::tag::Client::with_config(client, Config{window, ..Default::default()}).unwrap();
+    let timeout = Duration::from_secs(1);
+
+    let mut first = upsert("c_test");
+    first.value("alice").when(IP("10.0.0.16".parse().unwrap()));
+    first.value("bob").when(IP("10.0.0.32".parse().unwrap()));
+
+    let mut second = upsert("c_test");
+    second.value("alice").when(IP("10.0.0.17".parse().unwrap()));
+
+    let mut third = delete("c_test");
+    third.value("bob");
+
+    let receipts = vec![
+        request(first),
+        request(second),
+        request(third),
+    ].into_iter().map(|r| client.send("c_test", r, timeout).unwrap()).collect::<Vec<_>>();
+
+    let guids = receipts.iter().map(|r| r.wait_timeout(timeout).unwrap()).collect::<Vec<_>>();
+    assert!(guids.iter().all(|guid| guid == &guids[0]));
+    assert_eq!(Stats{success: 3, failure: 0}, client.stats());
+
+    let request = server.request(timeout).unwrap();
+    let body    = serde_json::from_slice::<Request>(&request.body()).unwrap();
+    let values  = body.upserts.iter().map(Upsert::value).collect::<Vec<_>>();
+
+    assert_eq!(vec!["alice"], values);
+    assert_eq!(Some("10.0.0.17"), body.upserts[0].criteria()[0].addr.first().map(String::as_str));
+    assert_eq!(vec![Delete{value: "bob".to_owned()}], body.deletes);
+    assert!(server.request(Duration::from_millis(100)).is_err());
+
+    client.stop().unwrap();
+}
+
+#[test]
+fn client_parallel_columns() {
+    let (client, _server) = pair();
+    let client  = kentik_api::tag::Client::with_config(client, Config{workers: 4, ..Default::default()}).unwrap();
+    let timeout = Duration::from_secs(1);
+
+    let mut change = upsert("c_test");
+    change.value("alice").when(IP("10.0.0.16".parse().unwrap()));
+    let request = request(change);
+
+    let slow = client.send("c_slow", request.clone(), timeout).unwrap();
+    let fast = (0..8).map(|n| {
+        client.send(&format!("c_fast_{}", n), request.clone(), timeout).unwrap()
+    }).collect::<Vec<_>>();
+
+    let wait = Duration::from_millis(250);
+    assert!(fast.iter().any(|r| r.wait_timeout(wait).is_ok()));
+    assert!(slow.wait_timeout(timeout).is_ok());
+
+    client.stop().unwrap();
+}
+
+#[test]
+fn client_column_order() {
+    let (client, server) = pair();
+    let client  = kentik_api::tag::Client::with_config(client, Config{workers: 4, ..Default::default()}).unwrap();
+    let timeout = Duration::from_secs(1);
+    let columns = ["c_a", "c_b", "c_c"];
+
+    let receipts = (0..10).flat_map(|n| columns.iter().map(move |column| (n, column))).map(|(n, column)| {
+        let mut change = upsert("c_test");
+        change.value(&format!("v{}", n)).when(Port(n));
+        client.send(column, request(change), timeout).unwrap()
+    }).collect::<Vec<_>>();
+
+    for receipt in receipts {
+        receipt.wait_timeout(timeout).unwrap();
+    }
+
+    let mut values = columns.iter().map(|_| Vec::new()).collect::<Vec<_>>();
+    while let Ok(request) = server.request(Duration::from_millis(100)) {
+        let body  = serde_json::from_slice::<Request>(&request.body()).unwrap();
+        let index = columns.iter().position(|c| request.path.contains(c)).unwrap();
+        values[index].extend(body.upserts.iter().map(|u| u.value().to_owned()));
+    }
+
+    let expect = (0..10).map(|n| format!("v{}", n)).collect::<Vec<_>>();
+    assert!(values.iter().all(|v| v == &expect));
+
+    client.stop().unwrap();
+}
+
+#[test]
+fn client_journal() {
+    let (client, server) = pair();
+    let dir     = tempdir();
+    let journal = Some(dir.clone());
+    let timeout = Duration::from_secs(1);
+
+    let (pending, _) = kentik_api::tag::journal::Journal::open(&dir).unwrap();
+    let mut change = upsert("c_test");
+    change.value("alice").when(IP("10.0.0.16".parse().unwrap()));
+    pending.push("c_test", &request(change)).unwrap();
+    drop(pending);
+
+    let client = kentik_api::tag::Client::with_config(client, Config{journal, ..Default::default()}).unwrap();
+
+    let replay = server.request(timeout).unwrap();
+    let body   = serde_json::from_slice::<Request>(&replay.body()).unwrap();
+    assert_eq!(vec!["alice"], body.upserts.iter().map(Upsert::value).collect::<Vec<_>>());
+
+    let mut change = upsert("c_test");
+    change.value("bob").when(IP("10.0.0.32".parse().unwrap()));
+    let request = request(change);
+
+    let ok  = client.send("c_test", request.clone(), timeout).unwrap();
+    let err = client.send("c_invalid", request, timeout).unwrap();
+
+    assert!(ok.wait_timeout(timeout).is_ok());
+    assert!(err.wait_timeout(timeout).is_err());
+
+    client.stop().unwrap();
+
+    assert_eq!(0, std::fs::read_dir(&dir).unwrap().count());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn client_journal_retry() {
+    let (client, server) = pair();
+    let dir     = tempdir();
+    let journal = Some(dir.clone());
+    let timeout = Duration::from_secs(10);
+
+    let client = kentik_api::tag::Client::with_config(client, Config{journal, ..Default::default()}).unwrap();
+
+    let mut first = upsert("c_test");
+    first.value("alice").when(IP("10.0.0.16".parse().unwrap()));
+    let first = request(first);
+
+    let mut second = upsert("c_test");
+    second.value("alice").when(IP("10.0.0.17".parse().unwrap()));
+    let second = request(second);
+
+    let receipts = vec![first.clone(), second.clone()].into_iter().map(|r| {
+        client.send("c_flaky", r, timeout).unwrap()
+    }).collect::<Vec<_>>();
+
+    for receipt in receipts {
+        assert!(receipt.wait_timeout(timeout).is_ok());
+    }
+
+    let bodies = std::iter::from_fn(|| server.request(Duration::from_millis(200)).ok()).map(|r| {
+        r.body().to_vec()
+    }).collect::<Vec<_>>();
+
+    let (last, retries) = bodies.split_last().unwrap();
+    assert_eq!(&serde_json::to_vec(&second).unwrap(), last);
+    assert!(retries.len() > 3);
+    assert!(retries.iter().all(|body| body == &serde_json::to_vec(&first).unwrap()));
+
+    client.stop().unwrap();
+    assert_eq!(0, std::fs::read_dir(&dir).unwrap().count());
+
+    let (client, _) = pair();
+    let journal     = Some(dir.clone());
+    let client      = kentik_api::tag::Client::with_config(client, Config{journal, ..Default::default()}).unwrap();
+    client.stop().unwrap();
+
+    assert!(server.request(Duration::from_millis(200)).is_err());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn client_journal_give_up() {
+    let (client, _server) = pair();
+    let dir     = tempdir();
+    let journal = Some(dir.clone());
+    let timeout = Duration::from_secs(10);
+
+    let client = kentik_api::tag::Client::with_config(client, Config{journal, retries: 1, ..Default::default()}).unwrap();
+
+    let mut change = upsert("c_test");
+    change.value("alice").when(IP("10.0.0.16".parse().unwrap()));
+    let request = request(change);
+
+    let receipt = client.send("c_503", request.clone(), timeout).unwrap();
+    assert_eq!(Err(Error::Status(503)), receipt.wait_timeout(timeout));
+
+    let receipt = client.send("c_503", request, timeout).unwrap();
+    assert_eq!(Err(Error::Status(503)), receipt.wait_timeout(timeout));
+
+    assert_eq!(2, client.stats().failure);
+    client.stop().unwrap();
+
+    assert_eq!(2, std::fs::read_dir(&dir).unwrap().count());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn validate_populators() {
+    let (client, _server) = pair();
+
+    let mut change = upsert("c_test");
+    change.value("alice").when(Port(22));
+    let request = request(change);
+
+    assert!(client.validate_populators("c_test", &request).unwrap().is_empty());
+
+    let violations = client.validate_populators("c_count", &request).unwrap();
+    let paths      = violations.iter().map(|v| v.path.as_str()).collect::<Vec<_>>();
+    assert_eq!(vec!["upserts[0].value"], paths);
+}
+
+#[test]
+fn export_populators() {
+    let (client, _server) = pair();
+
+    let mut csv = Vec::new();
+    client.export_populators("c_test", file::Format::Csv, &mut csv).unwrap();
+
+    let csv   = String::from_utf8(csv).unwrap();
+    let lines = csv.lines().collect::<Vec<_>>();
+    assert_eq!(4, lines.len());
+    assert!(lines[0].starts_with("value,direction,port,"));
+    assert!(lines[1].starts_with("alice,") && lines[1].contains(",10.0.0.16,"));
+
+    let request = file::import("c_test", file::Format::Csv, csv.as_bytes()).unwrap();
+    assert_eq!(vec!["alice", "bob", "eve"], request.upserts.iter().map(Upsert::value).collect::<Vec<_>>());
+}
+
+#[test]
+fn refresh_ttl_tags() {
+    let (client, server) = pair();
+    let client    = kentik_api::tag::Client::new(client);
+    let interval  = Box::new(|_| Duration::from_millis(100));
+    let refresher = refresh::Refresher::with_interval(client, interval);
+    let timeout   = Duration::from_secs(1);
+
+    let mut change = upsert("c_test");
+    change.value("alice").when(IP("10.0.0.16".parse().unwrap()));
+    change.value("bob").when(IP("10.0.0.32".parse().unwrap()));
+    let mut request = request(change);
+    request.ttl_minutes = 5;
+
+    refresher.send("c_test", request, timeout).unwrap().wait().unwrap();
+    assert!(server.request(timeout).is_ok());
+
+    let refresh = server.request(timeout).unwrap();
+    let body    = serde_json::from_slice::<Request>(&refresh.body()).unwrap();
+    let values  = body.upserts.iter().map(Upsert::value).collect::<Vec<_>>();
+    assert_eq!(vec!["alice", "bob"], values);
+    assert_eq!(5, body.ttl_minutes);
+
+    refresher.remove("c_test", "alice").unwrap();
+    refresher.remove("c_test", "bob").unwrap();
+    let idle = Duration::from_millis(300);
+    for _ in 0..3 {
+        if server.request(idle).is_err() {
+            break;
+        }
+    }
+    assert!(server.request(idle).is_err());
+
+    refresher.stop().unwrap();
+}
+
+#[test]
+fn refresh_permanent_upsert() {
+    let (client, server) = pair();
+    let client    = kentik_api::tag::Client::new(client);
+    let interval  = Box::new(|_| Duration::from_millis(100));
+    let refresher = refresh::Refresher::with_interval(client, interval);
+    let timeout   = Duration::from_secs(1);
+
+    let mut change = upsert("c_test");
+    change.value("alice").when(IP("10.0.0.16".parse().unwrap()));
+    let mut ttl = request(change);
+    ttl.ttl_minutes = 5;
+
+    refresher.send("c_test", ttl, timeout).unwrap().wait().unwrap();
+    assert!(server.request(timeout).is_ok());
+
+    let mut change = upsert("c_test");
+    change.value("alice").when(IP("10.0.0.17".parse().unwrap()));
+    let permanent = request(change);
+
+    refresher.send("c_test", permanent.clone(), timeout).unwrap().wait().unwrap();
+
+    let idle = Duration::from_millis(300);
+    let last = std::iter::from_fn(|| server.request(idle).ok()).take(10).last().unwrap();
+    let body = serde_json::from_slice::<Request>(&last.body()).unwrap();
+    assert_eq!(permanent, body);
+
+    refresher.stop().unwrap();
+}
+
+#[test]
+fn refresh_delete_due() {
+    let (client, server) = pair();
+    let client    = kentik_api::tag::Client::new(client);
+    let interval  = Box::new(|_| Duration::from_millis(1));
+    let refresher = refresh::Refresher::with_interval(client, interval);
+    let timeout   = Duration::from_secs(1);
+
+    let mut change = upsert("c_test");
+    change.value("alice").when(IP("10.0.0.16".parse().unwrap()));
+    let mut ttl = request(change);
+    ttl.ttl_minutes = 5;
+
+    refresher.send("c_test", ttl, timeout).unwrap().wait().unwrap();
+    assert!(server.request(timeout).is_ok());
+
+    let mut change = delete("c_test");
+    change.value("alice");
+    let removed = request(change);
+
+    refresher.send("c_test", removed.clone(), timeout).unwrap().wait().unwrap();
+
+    let idle = Duration::from_millis(300);
+    let last = std::iter::from_fn(|| server.request(idle).ok()).take(1000).last().unwrap();
+    let body = serde_json::from_slice::<Request>(&last.body()).unwrap();
+    assert_eq!(removed, body);
+
+    refresher.stop().unwrap();
+}
+
+#[test]
+fn analyze_populators() {
+    let (client, _server) = pair();
+    let report = client.analyze_populators("c_test").unwrap();
+    assert!(report.is_empty());
+}
+
+#[test]
+fn client_ensure_dimension() {
+    let (client, server) = pair();
+    let client  = kentik_api::tag::Client::new(client);
+    let timeout = Duration::from_millis(100);
+
+    let dimension = client.ensure("c_test", "Test", "string").unwrap();
+    assert_eq!(1, dimension.id);
+    assert_eq!("/api/internal/customdimensions", server.request(timeout).unwrap().path);
+
+    let dimension = client.ensure("c_new", "New", "string").unwrap();
+    assert_eq!(("c_new", "New"), (dimension.name.as_str(), dimension.display_name.as_str()));
+    assert_eq!("/api/internal/customdimensions", server.request(timeout).unwrap().path);
+    assert_eq!("/api/internal/customdimension", server.request(timeout).unwrap().path);
+
+    assert!(client.ensure("c_new", "New", "string").is_ok());
+    assert!(client.ensure("c_test", "Test", "string").is_ok());
+    assert!(server.request(timeout).is_err());
+
+    assert!(matches!(client.ensure("c_count", "Count", "string"), Err(Error::Invalid(_))));
+
+    client.stop().unwrap();
+}
+
+#[test]
+fn dry_run_populators() {
+    let (client, server) = pair();
+    let client  = kentik_api::tag::Client::new(client);
+    let timeout = Duration::from_millis(100);
+
+    let mut change = upsert("c_test");
+    change.value("alice").when(IP("10.0.0.16".parse().unwrap()));
+    change.value("mallory").when(IP("10.0.0.64".parse().unwrap()));
+    let mut request = request(change);
+    request.deletes.push(Delete{value: "eve".to_owned()});
+
+    let dry = client.dry_run("c_test", &request).unwrap();
+    let sent = serde_json::from_str::<Request>(&dry.bodies[0]).unwrap();
+    assert_eq!(request, sent);
+    assert_eq!("+ mallory\n- eve\n", dry.diff.unwrap().to_string());
+
+    assert_eq!("/api/internal/customdimension/c_test/populators", server.request(timeout).unwrap().path);
+    assert!(server.request(timeout).is_err());
+
+    let dry = client.dry_run("c_missing", &request).unwrap();
+    assert_eq!(1, dry.bodies.len());
+    assert_eq!(None, dry.diff);
+
+    assert!(matches!(client.dry_run("c_503", &request), Err(Error::Status(503))));
+
+    client.stop().unwrap();
+}
+
+fn pair() -> (Client, Server) {
+    let server = server::start("127.0.0.1:0", None, None);
+    let (email, token) = server.auth();
+    let endpoint = server.url("");
+    let client = Client::new(&email, &token, &endpoint, None).unwrap();
+    (client, server)
+}
+
+fn request<T: Into<Change>>(change: T) -> Request {
+    let mut batch = batch("c_test");
+    batch.change(change);
+    batch.build().unwrap().1
+}
+
+fn tempdir() -> std::path::PathBuf {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+    env::temp_dir().join(format!("kentik-tag-{}-{}", process::id(), nanos))
+}